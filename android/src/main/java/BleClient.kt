@@ -5,6 +5,9 @@ import android.Manifest
 import android.annotation.SuppressLint
 import android.app.Activity
 import android.bluetooth.BluetoothAdapter
+import android.bluetooth.BluetoothDevice
+import android.bluetooth.BluetoothGatt
+import android.bluetooth.BluetoothGattCallback
 import android.bluetooth.BluetoothManager
 import android.bluetooth.BluetoothProfile
 import android.bluetooth.le.BluetoothLeScanner
@@ -40,7 +43,9 @@ class BleDevice(
     private val connected: Boolean,
     private val manufacturerData: SparseArray<ByteArray>?,
     private val serviceData: Map<ParcelUuid, ByteArray>?,
-    private val services: List<ParcelUuid>?
+    private val services: List<ParcelUuid>?,
+    private val rawAdvertisement: ByteArray?,
+    private val paired: Boolean
 ){
     private val base64Encoder: Base64.Encoder = Base64.getEncoder()
 
@@ -81,6 +86,11 @@ class BleDevice(
             subObj
         } else { null }
         obj.put("serviceData",serviceData)
+        val rawAdvertisement = if (rawAdvertisement != null) {
+            base64Encoder.encodeToString(rawAdvertisement)
+        } else { null }
+        obj.put("rawAdvertisement",rawAdvertisement)
+        obj.put("paired",paired)
         return obj
     }
 }
@@ -144,6 +154,8 @@ class BleClient(private val activity: Activity, private val plugin: BleClientPlu
     class ScanParams {
         val services: ArrayList<String> = ArrayList()
         val onDevice: Channel? = null
+        val scanMode: String = "lowPower"
+        val extendedAdvertising: Boolean = false
     }
     @SuppressLint("MissingPermission")
     fun startScan(invoke: Invoke) {
@@ -184,9 +196,19 @@ class BleClient(private val activity: Activity, private val plugin: BleClientPlu
                 filters.add(Builder().setServiceUuid(ParcelUuid.fromString(uuid)).build())
             }
         }
-        val settings = ScanSettings.Builder()
+        val scanMode = when (args.scanMode) {
+            "lowLatency" -> ScanSettings.SCAN_MODE_LOW_LATENCY
+            "balanced" -> ScanSettings.SCAN_MODE_BALANCED
+            else -> ScanSettings.SCAN_MODE_LOW_POWER
+        }
+        val settingsBuilder = ScanSettings.Builder()
             .setCallbackType(ScanSettings.CALLBACK_TYPE_ALL_MATCHES)
-            .build()
+            .setScanMode(scanMode)
+        if (args.extendedAdvertising && Build.VERSION.SDK_INT >= Build.VERSION_CODES.O) {
+            settingsBuilder.setLegacy(false)
+            settingsBuilder.setPhy(ScanSettings.PHY_LE_ALL_SUPPORTED)
+        }
+        val settings = settingsBuilder.build()
 
         scanCb = object: ScanCallback(){
             private fun sendResult(result: ScanResult){
@@ -210,7 +232,9 @@ class BleClient(private val activity: Activity, private val plugin: BleClientPlu
                     connected,
                     result.scanRecord?.manufacturerSpecificData,
                     result.scanRecord?.serviceData,
-                    result.scanRecord?.serviceUuids
+                    result.scanRecord?.serviceUuids,
+                    result.scanRecord?.bytes,
+                    result.device.bondState == BluetoothDevice.BOND_BONDED
                 )
                 this@BleClient.plugin.devices[device.address] = Peripheral(this@BleClient.activity, result.device, this@BleClient.plugin)
                 val res = JSObject()
@@ -241,4 +265,54 @@ class BleClient(private val activity: Activity, private val plugin: BleClientPlu
         }
         invoke.resolve()
     }
+
+    fun adapterFeatures(invoke: Invoke){
+        val adapter = getSystemService(activity, BluetoothManager::class.java)?.adapter
+        if (adapter == null){
+            invoke.reject("No bluetooth adapter available")
+            return
+        }
+        val res = JSObject()
+        res.put("le2mPhy", adapter.isLe2MPhySupported)
+        res.put("leCodedPhy", adapter.isLeCodedPhySupported)
+        res.put("extendedAdvertising", adapter.isLeExtendedAdvertisingSupported)
+        // android has no API for the maximum number of connections
+        res.put("maxConnections", null)
+        invoke.resolve(res)
+    }
+
+    fun adapterState(invoke: Invoke){
+        val adapter = getSystemService(activity, BluetoothManager::class.java)?.adapter
+        if (adapter == null){
+            invoke.reject("No bluetooth adapter available")
+            return
+        }
+        val res = JSObject()
+        res.put("result", adapter.isEnabled)
+        invoke.resolve(res)
+    }
+
+    @SuppressLint("MissingPermission")
+    fun disconnectAll(invoke: Invoke){
+        val manager = getSystemService(activity, BluetoothManager::class.java)
+        if (manager == null){
+            invoke.reject("No bluetooth manager found")
+            return
+        }
+        for (device in this.plugin.connected_devices.values){
+            device.forceDisconnect()
+        }
+        this.plugin.connected_devices.clear()
+        // links held by the system, e.g. from a previous run of the app,
+        // are released by opening a gatt client and closing it again
+        for (device in manager.getConnectedDevices(BluetoothProfile.GATT)){
+            device.connectGatt(activity, false, object: BluetoothGattCallback(){
+                override fun onConnectionStateChange(gatt: BluetoothGatt?, status: Int, newState: Int) {
+                    gatt?.disconnect()
+                    gatt?.close()
+                }
+            })
+        }
+        invoke.resolve()
+    }
 }
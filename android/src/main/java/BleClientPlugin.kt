@@ -2,7 +2,13 @@ package com.plugin.blec
 
 
 import Peripheral
+import android.annotation.SuppressLint
 import android.app.Activity
+import android.bluetooth.BluetoothAdapter
+import android.bluetooth.BluetoothDevice
+import android.content.Intent
+import androidx.activity.result.ActivityResult
+import app.tauri.annotation.ActivityCallback
 import app.tauri.annotation.Command
 import app.tauri.annotation.InvokeArg
 import app.tauri.annotation.TauriPlugin
@@ -16,6 +22,7 @@ import java.util.UUID
 @InvokeArg
 class ConnectParams{
     val address: String = ""
+    val transport: Int = BluetoothDevice.TRANSPORT_LE
 }
 
 @TauriPlugin
@@ -23,6 +30,7 @@ class BleClientPlugin(private val activity: Activity): Plugin(activity) {
     var devices: MutableMap<String, Peripheral> = mutableMapOf();
     var connected_devices: MutableMap<String, Peripheral> = mutableMapOf();
     var eventChannel: Channel? = null;
+    var writeErrorChannel: Channel? = null;
     private val client = BleClient(activity,this)
 
     @Command
@@ -41,6 +49,12 @@ class BleClientPlugin(private val activity: Activity): Plugin(activity) {
         invoke.resolve()
     }
 
+    @Command
+    fun write_errors(invoke: Invoke){
+        this.writeErrorChannel = invoke.parseArgs(Channel::class.java)
+        invoke.resolve()
+    }
+
     @Command
     fun connect(invoke: Invoke){
         val args = invoke.parseArgs(ConnectParams::class.java)
@@ -50,7 +64,7 @@ class BleClientPlugin(private val activity: Activity): Plugin(activity) {
             return
         }
         this.connected_devices[args.address] = device;
-        device.connect(invoke)
+        device.connect(invoke, args.transport)
     }
 
     @Command
@@ -65,6 +79,11 @@ class BleClientPlugin(private val activity: Activity): Plugin(activity) {
         device.disconnect(invoke)
     }
 
+    @Command
+    fun disconnect_all(invoke: Invoke){
+        client.disconnectAll(invoke)
+    }
+
     @Command
     fun is_connected(invoke: Invoke){
         val args = invoke.parseArgs(ConnectParams::class.java)
@@ -174,6 +193,101 @@ class BleClientPlugin(private val activity: Activity): Plugin(activity) {
         device.subscribe(invoke,false)
     }
 
+    @Command
+    fun read_descriptors(invoke: Invoke){
+        val args = invoke.parseArgs(ReadParams::class.java)
+        val device = this.connected_devices[args.address]
+        if (device == null){
+            invoke.reject("Device not found")
+            return
+        }
+        device.readDescriptors(invoke)
+    }
+
+    @InvokeArg
+    class HandleParams(){
+        val address: String = ""
+        val handle: Int = 0
+        val data: ByteArray? = null
+        val withResponse: Boolean = true
+    }
+
+    @Command
+    fun write_by_handle(invoke: Invoke){
+        val args = invoke.parseArgs(HandleParams::class.java)
+        val device = this.connected_devices[args.address]
+        if (device == null){
+            invoke.reject("Device not found")
+            return
+        }
+        device.writeByHandle(invoke)
+    }
+
+    @Command
+    fun read_by_handle(invoke: Invoke){
+        val args = invoke.parseArgs(HandleParams::class.java)
+        val device = this.connected_devices[args.address]
+        if (device == null){
+            invoke.reject("Device not found")
+            return
+        }
+        device.readByHandle(invoke)
+    }
+
+    @InvokeArg
+    class DescriptorParams(){
+        val address: String = ""
+        val characteristic: UUID? = null
+        val descriptor: UUID? = null
+        val data: ByteArray? = null
+    }
+
+    @Command
+    fun read_descriptor(invoke: Invoke){
+        val args = invoke.parseArgs(DescriptorParams::class.java)
+        val device = this.connected_devices[args.address]
+        if (device == null){
+            invoke.reject("Device not found")
+            return
+        }
+        device.readDescriptor(invoke)
+    }
+
+    @Command
+    fun write_descriptor(invoke: Invoke){
+        val args = invoke.parseArgs(DescriptorParams::class.java)
+        val device = this.connected_devices[args.address]
+        if (device == null){
+            invoke.reject("Device not found")
+            return
+        }
+        device.writeDescriptor(invoke)
+    }
+
+    @Command
+    fun adapter_features(invoke: Invoke){
+        client.adapterFeatures(invoke)
+    }
+
+    @Command
+    fun adapter_state(invoke: Invoke){
+        client.adapterState(invoke)
+    }
+
+    @SuppressLint("MissingPermission")
+    @Command
+    fun request_enable_bluetooth(invoke: Invoke){
+        val intent = Intent(BluetoothAdapter.ACTION_REQUEST_ENABLE)
+        startActivityForResult(invoke, intent, "enableBluetoothResult")
+    }
+
+    @ActivityCallback
+    private fun enableBluetoothResult(invoke: Invoke, result: ActivityResult){
+        val res = JSObject()
+        res.put("result", result.resultCode == Activity.RESULT_OK)
+        invoke.resolve(res)
+    }
+
     @Command
     fun check_permissions(invoke: Invoke){
         val granted = client.checkPermissions();
@@ -182,6 +296,17 @@ class BleClientPlugin(private val activity: Activity): Plugin(activity) {
         invoke.resolve(ret);
     }
 
+    @Command
+    fun link_info(invoke: Invoke){
+        val args = invoke.parseArgs(ConnectParams::class.java)
+        val device = this.connected_devices[args.address]
+        if (device == null){
+            invoke.reject("Device not found")
+            return
+        }
+        device.linkInfo(invoke)
+    }
+
     @InvokeArg
     class MtuParams{
         val address: String = ""
@@ -32,11 +32,24 @@ class Peripheral(private val activity: Activity, private val device: BluetoothDe
     private val onWriteInvoke:MutableMap<UUID,Invoke> = mutableMapOf()
     private var onDescriptorInvoke: Invoke? = null
     private var onMtuInvoke: Invoke? = null
+    private var onLinkInfoInvoke: Invoke? = null
+    private var linkInfo: JSObject = JSObject()
+    private var onDescriptorReadInvoke: Invoke? = null
+    private var pendingDescriptorReads: ArrayDeque<BluetoothGattDescriptor> = ArrayDeque()
+    private var descriptorValues: JSONArray = JSONArray()
 
     private enum class Event{
         DeviceConnected,
         DeviceDisconnected
     }
+    private fun sendWriteError(characteristic: UUID, message: String){
+        val channel = this.plugin.writeErrorChannel ?: return
+        val data = JSObject()
+        data.put("characteristic", characteristic.toString())
+        data.put("message", message)
+        channel.send(data)
+    }
+
     private fun sendEvent(event: Event){
         val channel = this.plugin.eventChannel?: return
         val data = JSObject()
@@ -100,6 +113,9 @@ class Peripheral(private val activity: Activity, private val device: BluetoothDe
             status: Int
         ) {
             val id = characteristic?.uuid ?: return
+            if (status != BluetoothGatt.GATT_SUCCESS) {
+                this@Peripheral.sendWriteError(id, "write failed with status $status")
+            }
             synchronized(this@Peripheral.onWriteInvoke) {
                 val invoke = this@Peripheral.onWriteInvoke[id]
                 if (invoke == null) {
@@ -146,11 +162,55 @@ class Peripheral(private val activity: Activity, private val device: BluetoothDe
         ) {
             if (status != BluetoothGatt.GATT_SUCCESS){
                 this@Peripheral.onDescriptorInvoke?.reject("descriptor write failed with status: $status")
-            } else if (descriptor?.uuid != CLIENT_CHARACTERISTIC_CONFIGURATION_DESCRIPTOR){
-                this@Peripheral.onDescriptorInvoke?.reject("unexpected write to descriptor: ${descriptor?.uuid}")
             } else {
                 this@Peripheral.onDescriptorInvoke?.resolve()
             }
+            this@Peripheral.onDescriptorInvoke = null
+        }
+
+        override fun onDescriptorRead(
+            gatt: BluetoothGatt,
+            descriptor: BluetoothGattDescriptor,
+            status: Int,
+            value: ByteArray
+        ) {
+            this.descriptorRead(gatt, descriptor, status, value)
+        }
+
+        // only called below API 33, newer versions call the override with the value
+        @Deprecated("Deprecated in API 33")
+        @Suppress("DEPRECATION")
+        override fun onDescriptorRead(
+            gatt: BluetoothGatt,
+            descriptor: BluetoothGattDescriptor,
+            status: Int
+        ) {
+            if (Build.VERSION.SDK_INT < Build.VERSION_CODES.TIRAMISU) {
+                this.descriptorRead(gatt, descriptor, status, descriptor.value ?: ByteArray(0))
+            }
+        }
+
+        private fun descriptorRead(
+            gatt: BluetoothGatt,
+            descriptor: BluetoothGattDescriptor,
+            status: Int,
+            value: ByteArray
+        ) {
+            val invoke = this@Peripheral.onDescriptorReadInvoke
+            if (invoke == null) {
+                Log.e("Peripheral", "Did not find tauri invoke obj for read on descriptor ${descriptor.uuid}")
+                return
+            }
+            if (status != BluetoothGatt.GATT_SUCCESS) {
+                invoke.reject("Read from descriptor ${descriptor.uuid} failed with status $status")
+                this@Peripheral.onDescriptorReadInvoke = null
+                return
+            }
+            val entry = JSObject()
+            entry.put("uuid", descriptor.uuid)
+            entry.put("value", base64Encoder.encodeToString(value))
+            this@Peripheral.descriptorValues.put(entry)
+            this@Peripheral.readNextDescriptor(gatt)
         }
 
         override fun onMtuChanged(gatt: BluetoothGatt?, mtu: Int, status: Int) {
@@ -169,10 +229,30 @@ class Peripheral(private val activity: Activity, private val device: BluetoothDe
             }
             this@Peripheral.onMtuInvoke = null
         }
+
+        override fun onPhyRead(gatt: BluetoothGatt?, txPhy: Int, rxPhy: Int, status: Int) {
+            if (this@Peripheral.onLinkInfoInvoke == null) {
+                return
+            }
+            if (status == BluetoothGatt.GATT_SUCCESS) {
+                this@Peripheral.linkInfo.put("txPhy", txPhy)
+                this@Peripheral.linkInfo.put("rxPhy", rxPhy)
+            }
+            this@Peripheral.readLinkRssi()
+        }
+
+        override fun onReadRemoteRssi(gatt: BluetoothGatt?, rssi: Int, status: Int) {
+            val invoke = this@Peripheral.onLinkInfoInvoke ?: return
+            if (status == BluetoothGatt.GATT_SUCCESS) {
+                this@Peripheral.linkInfo.put("rssi", rssi)
+            }
+            invoke.resolve(this@Peripheral.linkInfo)
+            this@Peripheral.onLinkInfoInvoke = null
+        }
     }
 
     @SuppressLint("MissingPermission")
-    fun connect(invoke:Invoke) {
+    fun connect(invoke:Invoke, transport: Int) {
         this.onConnectionStateChange = { success, error ->
             if(success){
                 invoke.resolve()
@@ -181,7 +261,7 @@ class Peripheral(private val activity: Activity, private val device: BluetoothDe
             }
             this@Peripheral.onConnectionStateChange = null
         }
-        this.device.connectGatt(activity, false, this.callback)
+        this.device.connectGatt(activity, false, this.callback, transport)
     }
 
     @SuppressLint("MissingPermission")
@@ -209,9 +289,14 @@ class Peripheral(private val activity: Activity, private val device: BluetoothDe
 
     @SuppressLint("MissingPermission")
     fun disconnect(invoke: Invoke){
+        this.forceDisconnect()
+        invoke.resolve()
+    }
+
+    @SuppressLint("MissingPermission")
+    fun forceDisconnect(){
         this.gatt?.disconnect()
         this.connected = false
-        invoke.resolve()
     }
 
      class ResCharacteristic (
@@ -257,7 +342,7 @@ class Peripheral(private val activity: Activity, private val device: BluetoothDe
             for (charac in service.characteristics){
                 characs.add(ResCharacteristic(
                     charac.uuid.toString(),
-                    0,
+                    charac.properties,
                     charac.descriptors.map { desc ->  desc.uuid.toString()},
                 ))
             }
@@ -327,6 +412,63 @@ class Peripheral(private val activity: Activity, private val device: BluetoothDe
         gatt.readCharacteristic(charac)
     }
 
+    private fun characteristicByHandle(gatt: BluetoothGatt, handle: Int): BluetoothGattCharacteristic? {
+        // the instance id of a characteristic is its ATT value handle
+        return gatt.services.flatMap { it.characteristics }.find { it.instanceId == handle }
+    }
+
+    @SuppressLint("MissingPermission")
+    fun writeByHandle(invoke: Invoke){
+        val args = invoke.parseArgs(BleClientPlugin.HandleParams::class.java)
+        val gatt = this.gatt;
+        if (gatt == null){
+            invoke.reject("No gatt server connected")
+            return
+        }
+        val charac = characteristicByHandle(gatt, args.handle)
+        if (charac == null){
+            invoke.reject("No characteristic with handle ${args.handle} found")
+            return
+        }
+        synchronized(this.onWriteInvoke) {
+            if (this.onWriteInvoke[charac.uuid] != null) {
+                this.onWriteInvoke[charac.uuid]!!.reject("write was overwritten before finishing")
+            }
+            this.onWriteInvoke[charac.uuid] = invoke
+        }
+        val data = args.data ?: ByteArray(0)
+        if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.TIRAMISU) {
+            gatt.writeCharacteristic(charac,data,if (args.withResponse){BluetoothGattCharacteristic.WRITE_TYPE_DEFAULT}else{BluetoothGattCharacteristic.WRITE_TYPE_NO_RESPONSE})
+        } else {
+            @Suppress("DEPRECATION")
+            charac.value = data
+            @Suppress("DEPRECATION")
+            gatt.writeCharacteristic(charac)
+        }
+    }
+
+    @SuppressLint("MissingPermission")
+    fun readByHandle(invoke: Invoke){
+        val args = invoke.parseArgs(BleClientPlugin.HandleParams::class.java)
+        val gatt = this.gatt;
+        if (gatt == null){
+            invoke.reject("No gatt server connected")
+            return
+        }
+        val charac = characteristicByHandle(gatt, args.handle)
+        if (charac == null){
+            invoke.reject("No characteristic with handle ${args.handle} found")
+            return
+        }
+        synchronized(this.onReadInvoke) {
+            if (this.onReadInvoke[charac.uuid] != null) {
+                this.onReadInvoke[charac.uuid]!!.reject("read was overwritten before finishing")
+            }
+            this.onReadInvoke[charac.uuid] = invoke
+        }
+        gatt.readCharacteristic(charac)
+    }
+
     @SuppressLint("MissingPermission")
     fun subscribe(invoke: Invoke,enabled: Boolean){
         val args = invoke.parseArgs(BleClientPlugin.ReadParams::class.java)
@@ -357,6 +499,115 @@ class Peripheral(private val activity: Activity, private val device: BluetoothDe
         }
     }
 
+    @SuppressLint("MissingPermission")
+    private fun readNextDescriptor(gatt: BluetoothGatt){
+        val invoke = this.onDescriptorReadInvoke ?: return
+        val next = this.pendingDescriptorReads.removeFirstOrNull()
+        if (next == null){
+            val res = JSObject()
+            res.put("result", this.descriptorValues)
+            invoke.resolve(res)
+            this.onDescriptorReadInvoke = null
+            return
+        }
+        if (!gatt.readDescriptor(next)){
+            invoke.reject("Failed to read descriptor ${next.uuid}")
+            this.onDescriptorReadInvoke = null
+        }
+    }
+
+    fun readDescriptors(invoke: Invoke){
+        val args = invoke.parseArgs(BleClientPlugin.ReadParams::class.java)
+        val gatt = this.gatt;
+        if (gatt == null){
+            invoke.reject("No gatt server connected")
+            return
+        }
+        val charac = this.characteristics[args.characteristic!!]
+        if (charac == null){
+            invoke.reject("Characteristic ${args.characteristic} not found")
+            return
+        }
+        this.onDescriptorReadInvoke?.reject("descriptor read was overwritten before finishing")
+        this.onDescriptorReadInvoke = invoke
+        this.descriptorValues = JSONArray()
+        this.pendingDescriptorReads = ArrayDeque(charac.descriptors)
+        readNextDescriptor(gatt)
+    }
+
+    fun readDescriptor(invoke: Invoke){
+        val args = invoke.parseArgs(BleClientPlugin.DescriptorParams::class.java)
+        val gatt = this.gatt;
+        if (gatt == null){
+            invoke.reject("No gatt server connected")
+            return
+        }
+        val descriptor = this.characteristics[args.characteristic!!]?.getDescriptor(args.descriptor!!)
+        if (descriptor == null){
+            invoke.reject("Descriptor ${args.descriptor} of characteristic ${args.characteristic} not found")
+            return
+        }
+        // uses the same queue as readDescriptors with a single entry
+        this.onDescriptorReadInvoke?.reject("descriptor read was overwritten before finishing")
+        this.onDescriptorReadInvoke = invoke
+        this.descriptorValues = JSONArray()
+        this.pendingDescriptorReads = ArrayDeque(listOf(descriptor))
+        readNextDescriptor(gatt)
+    }
+
+    @SuppressLint("MissingPermission")
+    fun writeDescriptor(invoke: Invoke){
+        val args = invoke.parseArgs(BleClientPlugin.DescriptorParams::class.java)
+        val gatt = this.gatt;
+        if (gatt == null){
+            invoke.reject("No gatt server connected")
+            return
+        }
+        val descriptor = this.characteristics[args.characteristic!!]?.getDescriptor(args.descriptor!!)
+        if (descriptor == null){
+            invoke.reject("Descriptor ${args.descriptor} of characteristic ${args.characteristic} not found")
+            return
+        }
+        this.onDescriptorInvoke?.reject("descriptor write was overwritten before finishing")
+        this.onDescriptorInvoke = invoke
+        val data = args.data ?: ByteArray(0)
+        if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.TIRAMISU) {
+            gatt.writeDescriptor(descriptor,data)
+        } else {
+            @Suppress("DEPRECATION")
+            descriptor.value = data
+            @Suppress("DEPRECATION")
+            gatt.writeDescriptor(descriptor)
+        }
+    }
+
+    @SuppressLint("MissingPermission")
+    fun linkInfo(invoke: Invoke) {
+        val gatt = this.gatt
+        if (gatt == null) {
+            invoke.reject("No gatt server connected")
+            return
+        }
+        this.onLinkInfoInvoke?.reject("link info request was overwritten before finishing")
+        this.onLinkInfoInvoke = invoke
+        this.linkInfo = JSObject()
+        // the PHY is read first, the RSSI read is started from its callback
+        if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.O) {
+            gatt.readPhy()
+        } else {
+            readLinkRssi()
+        }
+    }
+
+    @SuppressLint("MissingPermission")
+    private fun readLinkRssi() {
+        if (this.gatt?.readRemoteRssi() != true) {
+            // resolve with what is known so far
+            this.onLinkInfoInvoke?.resolve(this.linkInfo)
+            this.onLinkInfoInvoke = null
+        }
+    }
+
     @SuppressLint("MissingPermission")
     fun requestMtu(invoke: Invoke, mtu: Int) {
         val gatt = this.gatt
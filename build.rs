@@ -3,16 +3,78 @@ const COMMANDS: &[&str] = &[
     "stop_scan",
     "connect",
     "disconnect",
+    "disconnect_all",
     "connection_state",
+    "connection_status",
     "send",
     "send_string",
     "recv",
     "recv_string",
+    "send_base64",
+    "recv_base64",
     "subscribe",
     "subscribe_string",
     "unsubscribe",
     "scanning_state",
+    "scan_complete",
     "check_permissions",
+    "recv_u8",
+    "recv_i8",
+    "recv_u16_le",
+    "recv_u16_be",
+    "recv_i16_le",
+    "recv_i16_be",
+    "recv_u32_le",
+    "recv_u32_be",
+    "recv_i32_le",
+    "recv_i32_be",
+    "pause_notifications",
+    "resume_notifications",
+    "loopback_test",
+    "send_chunked",
+    "connected_services",
+    "gatt_tree",
+    "adapter_features",
+    "reset",
+    "read_descriptor",
+    "write_descriptor",
+    "notification_age",
+    "send_by_handle",
+    "recv_by_handle",
+    "enable_notifications",
+    "disable_notifications",
+    "remove_listener",
+    "link_info",
+    "remember_device",
+    "remembered_devices",
+    "forget_device",
+    "read_service",
+    "supported_write_types",
+    "subscribe_when_ready",
+    "preferred_connection_parameters",
+    "watch_for_service",
+    "send_with_progress",
+    "characteristic_description",
+    "set_characteristic_description",
+    "subscribe_reassembled",
+    "set_keepalive",
+    "clear_keepalive",
+    "validate_connect_options",
+    "get_device",
+    "subscriptions",
+    "firmware_revision",
+    "set_subscribe_retries",
+    "inspect_characteristic",
+    "is_powered",
+    "request_enable_bluetooth",
+    "send_in_service",
+    "recv_in_service",
+    "scan_per_device",
+    "read_parallel",
+    "duplicate_characteristics",
+    "max_write_length",
+    "set_device_key",
+    "unwatch_for_service",
 ];
 
 fn main() {
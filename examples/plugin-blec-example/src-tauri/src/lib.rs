@@ -6,21 +6,12 @@ const CHARACTERISTIC_UUID: Uuid = uuid!("51FF12BB-3ED8-46E5-B4F9-D64E2FEC021B");
 // command to test the BLE communication from rust
 #[tauri::command]
 async fn test() -> bool {
-    const DATA: [u8; 500] = [0; 500];
     let handler = tauri_plugin_blec::get_handler().unwrap();
-    let start = std::time::Instant::now();
-    handler
-        .send_data(
-            CHARACTERISTIC_UUID,
-            &DATA,
-            tauri_plugin_blec::models::WriteType::WithoutResponse,
-        )
+    let stats = handler
+        .loopback_test(CHARACTERISTIC_UUID, CHARACTERISTIC_UUID, 500)
         .await
         .unwrap();
-    let response = handler.recv_data(CHARACTERISTIC_UUID).await.unwrap();
-    let time = start.elapsed();
-    info!("Time elapsed: {:?}", time);
-    assert_eq!(response, DATA);
+    info!("Time elapsed: {}ms", stats.round_trip_ms);
     true
 }
 
@@ -8,14 +8,110 @@ export type BleDevice = {
   services: string[];
   manufacturerData: Record<number, Uint8Array>;
   serviceData: Record<string, Uint8Array>;
+  /** Raw advertisement payload. Only available on Android, null on other platforms */
+  rawAdvertisement: Uint8Array | null;
+  /** Whether the device is bonded. Only available on Android, null on other platforms */
+  isPaired: boolean | null;
+  /** Tag given to the device when connecting, see ConnectOptions.tag */
+  tag: string | null;
+  /** Services and characteristics, only set for devices matching a service filter with ScanOptions.discoverFilteredServices */
+  gattServices: Service[] | null;
+};
+
+export type Characteristic = {
+  uuid: string;
+  /** Standard name if the UUID is defined by the Bluetooth SIG */
+  name: string | null;
+  descriptors: string[];
+  /** Bit flags of the characteristic properties */
+  properties: number;
+};
+
+export type Service = {
+  uuid: string;
+  /** Standard name if the UUID is defined by the Bluetooth SIG */
+  name: string | null;
+  /** Whether this is a primary service */
+  primary: boolean;
+  characteristics: Characteristic[];
+};
+
+export type GattTree = {
+  services: Service[];
+};
+
+/**
+ * UUID of a service, characteristic or descriptor.
+ * 16-bit and 32-bit SIG assigned UUIDs can be given in short form, e.g. `0x2A37` or `'2A37'`,
+ * they are expanded with the Bluetooth base UUID
+ */
+export type GattUuid = string | number;
+
+export type ScanOptions = {
+  /** Minimum time in milliseconds between two device updates. Updates in between are coalesced */
+  updateInterval?: number;
+  /** Only send devices that are new or changed since the last update */
+  onlyChanged?: boolean;
+  /**
+   * Report the services of devices that don't advertise any but are connected already.
+   * Devices are not connected for this, use discoverFilteredServices for that.
+   */
+  discoverServices?: boolean;
+  /**
+   * Discover the services and characteristics of devices matching a service filter, see BleDevice.gattServices.
+   * Every matching device is briefly connected once per scan, ignored without a service filter.
+   */
+  discoverFilteredServices?: boolean;
+  /** Maximum number of devices to keep, only the devices with the strongest signal are reported */
+  maxDevices?: number;
+  /**
+   * Resolve the names of devices that don't advertise one by connecting and reading the Device Name characteristic.
+   * Disabled by default because it briefly connects to every nameless device, which costs battery and delays results.
+   */
+  resolveNames?: boolean;
+  /** Order of the reported devices, defaults to 'address'. 'rssiDesc' sorts the strongest signal first */
+  sortBy?: 'address' | 'rssiDesc' | 'name';
+  /**
+   * Only report devices that have this characteristic.
+   * Every device passing the filter is briefly connected to discover its services, combine it with a service filter.
+   */
+  requireCharacteristic?: string;
+  /**
+   * Battery usage vs discovery speed, defaults to 'lowPower'. Use 'lowLatency' for scans the user is waiting for.
+   * Only used on Android
+   */
+  scanMode?: 'lowPower' | 'balanced' | 'lowLatency';
+  /**
+   * Also report devices using extended advertising, e.g. long range sensors.
+   * Used on Android 8 and newer, Linux reports them whenever the adapter supports it
+   */
+  extendedAdvertising?: boolean;
+  /** Throw an error if a scan is already running instead of replacing it */
+  failIfScanning?: boolean;
 };
 
+/**
+ * Filter for scanned devices, only matching devices are reported.
+ * Manufacturer data is given as `[companyId, data]`, the masked variant as `[companyId, data, mask]`
+ */
+export type ScanFilter =
+  | 'none'
+  | { service: string }
+  | { anyService: string[] }
+  | { allServices: string[] }
+  | { manufacturerData: [number, number[]] }
+  | { manufacturerDataMasked: [number, number[], number[]] };
+
 /**
   * Scan for BLE devices
+  * Starting a scan while another one is running replaces it and the handler of the replaced scan stops receiving
+  * updates, unless `failIfScanning` is set
   * @param handler - A function that will be called with an array of devices found during the scan
   * @param timeout - The scan timeout in milliseconds
+  * @param options - Options to control how often and which devices are reported
+  * @param filter - Only report devices matching this filter
 */
-export async function startScan(handler: (devices: BleDevice[]) => void, timeout: Number) {
+export async function startScan(handler: (devices: BleDevice[]) => void, timeout: Number, options: ScanOptions | null = null, filter: ScanFilter | null = null) {
   if (!timeout) {
     timeout = 10000;
   }
@@ -23,7 +119,32 @@ export async function startScan(handler: (devices: BleDevice[]) => void, timeout
   onDevices.onmessage = handler;
   await invoke<BleDevice[]>('plugin:blec|scan', {
     timeout,
-    onDevices
+    onDevices,
+    options,
+    filter
+  })
+}
+
+/**
+  * Scan for BLE devices and get every new device on its own as soon as it is found, instead of the list of all devices
+  * Each device is reported once per scan, which lets a picker show the first device with less delay.
+  * Devices are reported before names are resolved or services discovered
+  * @param handler - A function that will be called with each device found during the scan
+  * @param timeout - The scan timeout in milliseconds
+  * @param options - Options to control which devices are reported
+  * @param filter - Only report devices matching this filter
+*/
+export async function startScanPerDevice(handler: (device: BleDevice) => void, timeout: Number, options: ScanOptions | null = null, filter: ScanFilter | null = null) {
+  if (!timeout) {
+    timeout = 10000;
+  }
+  let onDevice = new Channel<BleDevice>();
+  onDevice.onmessage = handler;
+  await invoke('plugin:blec|scan_per_device', {
+    timeout,
+    onDevice,
+    options,
+    filter
   })
 }
 
@@ -45,6 +166,7 @@ export async function checkPermissions(): Promise<boolean> {
 
 /**
   * Register a handler to receive updates when the connection state changes
+  * Registering a new handler replaces the previous one
 */
 export async function getConnectionUpdates(handler: (connected: boolean) => void) {
   let connection_chan = new Channel<boolean>()
@@ -52,8 +174,22 @@ export async function getConnectionUpdates(handler: (connected: boolean) => void
   await invoke('plugin:blec|connection_state', { update: connection_chan })
 }
 
+export type ConnectionStatus = 'disconnected' | 'connecting' | 'connected' | 'reconnecting';
+
+/**
+  * Register a handler to receive updates of the detailed connection status
+  * In contrast to `getConnectionUpdates` this also reports running connection attempts and retries
+  * Registering a new handler replaces the previous one
+*/
+export async function getConnectionStatusUpdates(handler: (status: ConnectionStatus) => void) {
+  let status_chan = new Channel<ConnectionStatus>()
+  status_chan.onmessage = handler
+  await invoke('plugin:blec|connection_status', { update: status_chan })
+}
+
 /**
  * Register a handler to receive updates when the scanning state changes
+ * Registering a new handler replaces the previous one
  */
 export async function getScanningUpdates(handler: (scanning: boolean) => void) {
   let scanning_chan = new Channel<boolean>()
@@ -61,6 +197,17 @@ export async function getScanningUpdates(handler: (scanning: boolean) => void) {
   await invoke('plugin:blec|scanning_state', { update: scanning_chan })
 }
 
+/**
+ * Register a handler that is called when a scan ends because its timeout elapsed
+ * The handler receives the number of devices found during the scan
+ * Registering a new handler replaces the previous one
+ */
+export async function getScanCompleteUpdates(handler: (deviceCount: number) => void) {
+  let complete_chan = new Channel<number>()
+  complete_chan.onmessage = handler
+  await invoke('plugin:blec|scan_complete', { update: complete_chan })
+}
+
 /**
   * Disconnect from the currently connected device
 */
@@ -68,33 +215,237 @@ export async function disconnect() {
   await invoke('plugin:blec|disconnect')
 }
 
+/**
+  * Disconnect all devices connected to the adapter
+  * This also closes connections that were left open by a previous run of the app
+*/
+export async function disconnectAll() {
+  await invoke('plugin:blec|disconnect_all')
+}
+
+/** Capabilities of the local Bluetooth controller, null if the platform doesn't expose them */
+export type AdapterFeatures = {
+  le2mPhy: boolean | null;
+  leCodedPhy: boolean | null;
+  extendedAdvertising: boolean | null;
+  maxConnections: number | null;
+};
+
+/**
+ * Check if the Bluetooth adapter is powered on, e.g. before scanning or connecting
+ */
+export async function isPowered(): Promise<boolean> {
+  return await invoke<boolean>('plugin:blec|is_powered')
+}
+
+/**
+ * Ask the user to turn on Bluetooth with the system dialog.
+ * Only available on Android, throws a NotSupported error on other platforms
+ * @returns Whether the user enabled Bluetooth
+ */
+export async function requestEnableBluetooth(): Promise<boolean> {
+  return await invoke<boolean>('plugin:blec|request_enable_bluetooth')
+}
+
+/**
+ * Get the optional features supported by the local Bluetooth controller
+ * Only available on Android, all fields are null on other platforms
+ */
+export async function adapterFeatures(): Promise<AdapterFeatures> {
+  return await invoke<AdapterFeatures>('plugin:blec|adapter_features')
+}
+
+export type RememberedDevice = {
+  address: string;
+  name: string;
+  /** Services advertised by the device when it was remembered */
+  services: string[];
+  tag: string | null;
+};
+
+/**
+ * Store a discovered device in the app data dir, to connect to it directly on the next launch
+ * @param address Address of the device
+ * @param tag Optional tag for the device, see ConnectOptions.tag
+ */
+export async function rememberDevice(address: string, tag: string | null = null): Promise<RememberedDevice> {
+  return await invoke<RememberedDevice>('plugin:blec|remember_device', {
+    address,
+    tag
+  })
+}
+
+/**
+ * Get the devices stored with `rememberDevice`
+ */
+export async function rememberedDevices(): Promise<RememberedDevice[]> {
+  return await invoke<RememberedDevice[]>('plugin:blec|remembered_devices')
+}
+
+/**
+ * Remove a device stored with `rememberDevice`
+ * @param address Address of the device
+ */
+export async function forgetDevice(address: string) {
+  await invoke('plugin:blec|forget_device', {
+    address
+  })
+}
+
+/**
+ * Get MTU, PHY, connection interval and RSSI of the current connection in one call
+ * Values the platform can't provide are null
+ */
+export async function linkInfo(): Promise<LinkInfo> {
+  return await invoke<LinkInfo>('plugin:blec|link_info')
+}
+
+/**
+ * Read the Peripheral Preferred Connection Parameters characteristic (0x2A04) of the connected device
+ */
+export async function preferredConnectionParameters(): Promise<PreferredConnParams> {
+  return await invoke<PreferredConnParams>('plugin:blec|preferred_connection_parameters')
+}
+
+/**
+ * Read the Firmware Revision String characteristic (0x2A26) of the connected device, e.g. to check if an update is needed
+ */
+export async function firmwareRevision(): Promise<string> {
+  return await invoke<string>('plugin:blec|firmware_revision')
+}
+
+/**
+ * Get the current information of a device found by a previous scan
+ * @param address Address of the device
+ * @returns The device or null if no scan found it
+ */
+export async function getDevice(address: string): Promise<BleDevice | null> {
+  return await invoke<BleDevice | null>('plugin:blec|get_device', {
+    address
+  })
+}
+
+/**
+ * Check if connecting with the given options is supported on this platform without connecting.
+ * Throws an error describing the first unsupported option
+ * @param options Options to check
+ */
+export async function validateConnectOptions(options: ConnectOptions) {
+  await invoke('plugin:blec|validate_connect_options', {
+    options
+  })
+}
+
+/**
+ * Stop everything and reinitialize the Bluetooth adapter
+ * Use this to recover when scans and connects keep failing without restarting the app
+ */
+export async function reset() {
+  await invoke('plugin:blec|reset')
+}
+
+export type Phy = 'le1M' | 'le2M' | 'leCoded';
+
+/** Parameters of the current connection, null if the platform can't provide them */
+export type LinkInfo = {
+  mtu: number | null;
+  /** Only available on Android */
+  txPhy: Phy | null;
+  /** Only available on Android */
+  rxPhy: Phy | null;
+  connectionIntervalMs: number | null;
+  /** On platforms other than Android this is the RSSI of the last advertisement */
+  rssi: number | null;
+};
+
+/**
+ * Connection parameters the device prefers.
+ * Intervals are in units of 1.25ms and the timeout in units of 10ms, 0xFFFF means no preference
+ */
+export type PreferredConnParams = {
+  minInterval: number;
+  maxInterval: number;
+  slaveLatency: number;
+  supervisionTimeout: number;
+};
+
+export type ConnectProgress = 'connecting' | 'connected' | 'discoveringServices' | 'servicesDiscovered' | 'ready';
+
+export type ConnectOptions = {
+  /** Transport to use for dual-mode devices, defaults to 'le'. Only used on Android */
+  transport?: 'auto' | 'le' | 'brEdr';
+  /** Number of additional scans if the device was not found before connecting, defaults to 2 */
+  scanRetries?: number;
+  /**
+   * Stop a running scan before connecting, defaults to true.
+   * If disabled, connecting while a scan is running fails with a ScanInProgress error
+   */
+  stopScan?: boolean;
+  /** Tag for the device, e.g. 'left sensor'. Reported with the device in scans and connectedDevice, also after reconnects */
+  tag?: string;
+  /**
+   * What to do if a different device is still connected, defaults to 'disconnectExisting'.
+   * With 'reject' connecting fails with an AnotherDeviceConnected error
+   */
+  existingConnection?: 'disconnectExisting' | 'reject';
+  /**
+   * Disable notifications of all characteristics right after connecting, before subscribing.
+   * Helps with devices that keep sending notifications enabled by a previous connection
+   */
+  resetNotifications?: boolean;
+};
+
 /**
   * Connect to a BLE device
   * @param address - The address of the device to connect to
   * @param onDisconnect - A function that will be called when the device disconnects
+  * @param options - Options for the connection
+  * @param onProgress - A function that will be called with the stages of the connection attempt
 */
-export async function connect(address: string, onDisconnect: (() => void) | null) {
+export async function connect(address: string, onDisconnect: (() => void) | null, options: ConnectOptions | null = null, onProgress: ((stage: ConnectProgress) => void) | null = null) {
   console.log('connect', address)
   let disconnectChannel = new Channel()
   if (onDisconnect) {
     disconnectChannel.onmessage = onDisconnect
   }
+  let progressChannel = new Channel<ConnectProgress>()
+  if (onProgress) {
+    progressChannel.onmessage = onProgress
+  }
   try {
     await invoke('plugin:blec|connect', {
       address: address,
-      onDisconnect: disconnectChannel
+      onDisconnect: disconnectChannel,
+      options,
+      onProgress: progressChannel
     })
   } catch (e) {
     console.error(e)
   }
 }
 
+/**
+ * Get the services of the connected device as discovered during connect
+ * This doesn't communicate with the device and is cheap to call repeatedly
+ */
+export async function connectedServices(): Promise<Service[]> {
+  return await invoke<Service[]>('plugin:blec|connected_services')
+}
+
+/**
+ * Get the complete services, characteristics and descriptors tree of the connected device in one call
+ */
+export async function gattTree(): Promise<GattTree> {
+  return await invoke<GattTree>('plugin:blec|gatt_tree')
+}
+
 /**
  * Write a Uint8Array to a BLE characteristic
  * @param characteristic UUID of the characteristic to write to
  * @param data Data to write to the characteristic
+ * @param writeType Write type to use, uses the default write type of the handler if not set
  */
-export async function send(characteristic: string, data: Uint8Array, writeType: 'withResponse' | 'withoutResponse' = 'withResponse') {
+export async function send(characteristic: GattUuid, data: Uint8Array, writeType: 'withResponse' | 'withoutResponse' | null = null) {
   await invoke('plugin:blec|send', {
     characteristic,
     data,
@@ -102,12 +453,55 @@ export async function send(characteristic: string, data: Uint8Array, writeType:
   })
 }
 
+/**
+ * Get the largest payload a single write can carry, e.g. to size the messages of a protocol.
+ * This is the MTU minus the 3 byte ATT header, 20 if no larger MTU was negotiated.
+ * The limit is the same for both write types
+ */
+export async function maxWriteLength(): Promise<number> {
+  return await invoke<number>('plugin:blec|max_write_length')
+}
+
+/**
+ * Write data larger than the MTU to a BLE characteristic by splitting it into chunks
+ * @param characteristic UUID of the characteristic to write to
+ * @param data Data to write to the characteristic
+ * @param policy Write types used for the chunks, defaults to 'withResponse' for every chunk.
+ * 'fastWithFinalAck' writes all chunks without response except the last one
+ */
+export async function sendChunked(characteristic: GattUuid, data: Uint8Array, policy: 'withResponse' | 'withoutResponse' | 'fastWithFinalAck' | null = null) {
+  await invoke('plugin:blec|send_chunked', {
+    characteristic,
+    data,
+    policy,
+  })
+}
+
+/**
+ * Write data larger than the MTU to a BLE characteristic in chunks and report the progress
+ * @param characteristic UUID of the characteristic to write to
+ * @param data Data to write to the characteristic
+ * @param onProgress Called after each chunk with the written fraction between 0 and 1
+ * @param writeType Write type used for all chunks, uses the default write type of the handler if not set
+ */
+export async function sendWithProgress(characteristic: GattUuid, data: Uint8Array, onProgress: (progress: number) => void, writeType: 'withResponse' | 'withoutResponse' | null = null) {
+  let channel = new Channel<number>()
+  channel.onmessage = onProgress;
+  await invoke('plugin:blec|send_with_progress', {
+    characteristic,
+    data,
+    writeType,
+    onProgress: channel,
+  })
+}
+
 /**
  * Write a string to a BLE characteristic
  * @param characteristic UUID of the characteristic to write to
  * @param data Data to write to the characteristic
+ * @param writeType Write type to use, uses the default write type of the handler if not set
  */
-export async function sendString(characteristic: string, data: string, writeType: 'withResponse' | 'withoutResponse' = 'withResponse') {
+export async function sendString(characteristic: GattUuid, data: string, writeType: 'withResponse' | 'withoutResponse' | null = null) {
   await invoke('plugin:blec|send_string', {
     characteristic,
     data,
@@ -115,49 +509,503 @@ export async function sendString(characteristic: string, data: string, writeType
   })
 }
 
+/**
+ * Write base64 encoded data to a BLE characteristic
+ * This is much faster than `send` for large payloads, because the data is not transferred as an array of numbers
+ * @param characteristic UUID of the characteristic to write to
+ * @param data Base64 encoded data to write to the characteristic
+ * @param writeType Write type to use, uses the default write type of the handler if not set
+ */
+export async function sendBase64(characteristic: GattUuid, data: string, writeType: 'withResponse' | 'withoutResponse' | null = null) {
+  await invoke('plugin:blec|send_base64', {
+    characteristic,
+    data,
+    writeType,
+  })
+}
+
+/**
+ * Write a Uint8Array to a characteristic of a specific service.
+ * Use this if the device has the same characteristic UUID in several services
+ * @param service UUID of the service the characteristic belongs to
+ * @param characteristic UUID of the characteristic to write to
+ * @param data Data to write to the characteristic
+ * @param writeType Write type to use, uses the default write type of the handler if not set
+ */
+export async function sendInService(service: GattUuid, characteristic: GattUuid, data: Uint8Array, writeType: 'withResponse' | 'withoutResponse' | null = null) {
+  await invoke('plugin:blec|send_in_service', {
+    service,
+    characteristic,
+    data,
+    writeType,
+  })
+}
+
+/**
+ * Read bytes from a characteristic of a specific service.
+ * Use this if the device has the same characteristic UUID in several services
+ * @param service UUID of the service the characteristic belongs to
+ * @param characteristic UUID of the characteristic to read from
+ */
+export async function readInService(service: GattUuid, characteristic: GattUuid): Promise<Uint8Array> {
+  return await invoke<Uint8Array>('plugin:blec|recv_in_service', {
+    service,
+    characteristic
+  })
+}
+
 /**
  * Read bytes from a BLE characteristic
  * @param characteristic UUID of the characteristic to read from
  */
-export async function read(characteristic: string): Promise<Uint8Array> {
+export async function read(characteristic: GattUuid): Promise<Uint8Array> {
   let res = await invoke<Uint8Array>('plugin:blec|recv', {
     characteristic
   })
   return res
 }
 
+/**
+ * Get the write types a characteristic of the connected device supports.
+ * The list is empty if the characteristic is not writable
+ * @param characteristic UUID of the characteristic
+ */
+export async function supportedWriteTypes(
+  characteristic: GattUuid
+): Promise<('withResponse' | 'withoutResponse')[]> {
+  return await invoke<('withResponse' | 'withoutResponse')[]>('plugin:blec|supported_write_types', {
+    characteristic
+  })
+}
+
+/**
+ * Read all readable characteristics of a service of the connected device.
+ * Characteristics without the read property are skipped.
+ * @param service UUID of the service
+ * @returns The values by characteristic UUID
+ */
+export async function readService(service: GattUuid): Promise<Record<string, Uint8Array>> {
+  return await invoke<Record<string, Uint8Array>>('plugin:blec|read_service', {
+    service
+  })
+}
+
+/**
+ * Get the characteristic UUIDs the connected device uses in more than one service.
+ * Use sendInService and readInService for these, other functions use the characteristic of a primary service
+ */
+export async function duplicateCharacteristics(): Promise<string[]> {
+  return await invoke<string[]>('plugin:blec|duplicate_characteristics')
+}
+
+/** Result of reading one characteristic with readParallel */
+export type ReadResult = { value: number[] } | { error: string };
+
+/**
+ * Read several characteristics concurrently. A failing read only fails its own entry.
+ * Platforms that run GATT operations one after another still return all results, just not faster
+ * @param characteristics UUIDs of the characteristics to read
+ * @returns The result of each read by characteristic UUID
+ */
+export async function readParallel(characteristics: GattUuid[]): Promise<Record<string, ReadResult>> {
+  return await invoke<Record<string, ReadResult>>('plugin:blec|read_parallel', {
+    characteristics
+  })
+}
+
+/**
+ * Write to a characteristic identified by its ATT handle instead of the UUID.
+ * Only supported on Android
+ * @param handle ATT value handle of the characteristic
+ * @param data Data to write to the characteristic
+ * @param writeType Write type to use, uses the default write type of the handler if not set
+ */
+export async function sendByHandle(handle: number, data: Uint8Array, writeType: 'withResponse' | 'withoutResponse' | null = null) {
+  await invoke('plugin:blec|send_by_handle', {
+    handle,
+    data,
+    writeType,
+  })
+}
+
+/**
+ * Read a characteristic identified by its ATT handle instead of the UUID.
+ * Only supported on Android
+ * @param handle ATT value handle of the characteristic
+ */
+export async function readByHandle(handle: number): Promise<Uint8Array> {
+  let res = await invoke<Uint8Array>('plugin:blec|recv_by_handle', {
+    handle
+  })
+  return res
+}
+
 /**
  * Read a string from a BLE characteristic
  * @param characteristic UUID of the characteristic to read from
  */
-export async function readString(characteristic: string): Promise<string> {
+export async function readString(characteristic: GattUuid): Promise<string> {
   let res = await invoke<string>('plugin:blec|recv_string', {
     characteristic
   })
   return res
 }
 
+/**
+ * Read base64 encoded data from a BLE characteristic
+ * @param characteristic UUID of the characteristic to read from
+ */
+export async function readBase64(characteristic: GattUuid): Promise<string> {
+  return await invoke<string>('plugin:blec|recv_base64', {
+    characteristic
+  })
+}
+
+export type LoopbackStats = {
+  /** Number of bytes written and received back */
+  bytes: number;
+  /** Time from starting the write until the echo was received in milliseconds */
+  roundTripMs: number;
+  /** Payload bytes per second for the whole round trip */
+  bytesPerSecond: number;
+};
+
+/**
+ * Run a round trip test against a device that echoes written data
+ * Writes `size` random bytes and verifies they are received back as a notification or read
+ * @param writeCharacteristic UUID of the characteristic to write to
+ * @param echoCharacteristic UUID of the characteristic the data is echoed on, can be the same as writeCharacteristic
+ * @param size Number of bytes to send
+ */
+export async function loopbackTest(writeCharacteristic: GattUuid, echoCharacteristic: GattUuid, size: number): Promise<LoopbackStats> {
+  return await invoke<LoopbackStats>('plugin:blec|loopback_test', {
+    writeCharacteristic,
+    echoCharacteristic,
+    size
+  })
+}
+
+export type NumberType = 'u8' | 'i8' | 'u16' | 'i16' | 'u32' | 'i32';
+
+/**
+ * Read a fixed width number from a BLE characteristic
+ * @param characteristic UUID of the characteristic to read from
+ * @param type Type of the number, the characteristic value must have exactly this size
+ * @param endianness Byte order of the value, ignored for single byte types
+ */
+export async function readNumber(characteristic: GattUuid, type: NumberType, endianness: 'little' | 'big' = 'little'): Promise<number> {
+  let command = type === 'u8' || type === 'i8' ? type : `${type}_${endianness === 'little' ? 'le' : 'be'}`
+  return await invoke<number>(`plugin:blec|recv_${command}`, {
+    characteristic
+  })
+}
+
 /**
  * Unsubscribe from a BLE characteristic
  * @param characteristic UUID of the characteristic to unsubscribe from
  */
-export async function unsubscribe(characteristic: string) {
+export async function unsubscribe(characteristic: GattUuid) {
   await invoke('plugin:blec|unsubscribe', {
     characteristic
   })
 }
 
+/** Value and descriptor values of a characteristic */
+export type CharacteristicInspection = {
+  uuid: string;
+  /** Bit flags of the characteristic properties */
+  properties: number;
+  /** Value of the characteristic, null if it is not readable or reading failed */
+  value: number[] | null;
+  /** UUIDs and values of the descriptors that could be read */
+  descriptors: [string, number[]][];
+};
+
+/**
+ * Read the value of a characteristic and all its descriptors in one call, e.g. for a GATT explorer.
+ * Values that can't be read are left out instead of failing
+ * @param characteristic UUID of the characteristic to inspect
+ */
+export async function inspectCharacteristic(characteristic: GattUuid): Promise<CharacteristicInspection> {
+  return await invoke<CharacteristicInspection>('plugin:blec|inspect_characteristic', {
+    characteristic
+  })
+}
+
+/**
+ * Read the value of a descriptor
+ * @param characteristic UUID of the characteristic the descriptor belongs to
+ * @param descriptor UUID of the descriptor to read
+ */
+export async function readDescriptor(characteristic: GattUuid, descriptor: GattUuid): Promise<Uint8Array> {
+  let res = await invoke<number[]>('plugin:blec|read_descriptor', {
+    characteristic,
+    descriptor
+  })
+  return new Uint8Array(res)
+}
+
+/**
+ * Write the value of a descriptor
+ * @param characteristic UUID of the characteristic the descriptor belongs to
+ * @param descriptor UUID of the descriptor to write
+ * @param data Data to write to the descriptor
+ */
+export async function writeDescriptor(characteristic: GattUuid, descriptor: GattUuid, data: Uint8Array) {
+  await invoke('plugin:blec|write_descriptor', {
+    characteristic,
+    descriptor,
+    data
+  })
+}
+
+/**
+ * Periodically read a characteristic to keep an idle connection active, for devices that drop idle connections.
+ * Replaces a previous keepalive and ends when the device disconnects
+ * @param characteristic UUID of a readable characteristic, reading it should have no side effects
+ * @param intervalMs Time between two reads in milliseconds, at least 100
+ */
+export async function setKeepalive(characteristic: GattUuid, intervalMs: number) {
+  await invoke('plugin:blec|set_keepalive', {
+    characteristic,
+    intervalMs
+  })
+}
+
+/**
+ * Stop the keepalive started with `setKeepalive`
+ */
+export async function clearKeepalive() {
+  await invoke('plugin:blec|clear_keepalive')
+}
+
+/**
+ * Read the user description (descriptor 0x2901) of a characteristic, e.g. "Temperature (°C)"
+ * @param characteristic UUID of the characteristic
+ * @returns The description or null if the characteristic has none
+ */
+export async function characteristicDescription(characteristic: GattUuid): Promise<string | null> {
+  return await invoke<string | null>('plugin:blec|characteristic_description', {
+    characteristic
+  })
+}
+
+/**
+ * Write the user description (descriptor 0x2901) of a characteristic, only works if the device allows it
+ * @param characteristic UUID of the characteristic
+ * @param description New description
+ */
+export async function setCharacteristicDescription(characteristic: GattUuid, description: string) {
+  await invoke('plugin:blec|set_characteristic_description', {
+    characteristic,
+    description
+  })
+}
+
+/**
+ * Filter applied to notifications before they are sent to the frontend
+ * Matches if the notification contains `value` starting at byte `offset`
+ */
+export type NotifyFilter = {
+  offset: number;
+  value: number[];
+};
+
+/**
+ * Pause delivering notifications of a characteristic without unsubscribing on the device
+ * The latest value received while paused is delivered on resume
+ * @param characteristic UUID of the characteristic to pause
+ */
+export async function pauseNotifications(characteristic: GattUuid) {
+  await invoke('plugin:blec|pause_notifications', {
+    characteristic
+  })
+}
+
+/**
+ * Resume notifications paused with `pauseNotifications`
+ * @param characteristic UUID of the characteristic to resume
+ */
+export async function resumeNotifications(characteristic: GattUuid) {
+  await invoke('plugin:blec|resume_notifications', {
+    characteristic
+  })
+}
+
+/**
+ * Enable notifications of a characteristic on the device without registering a handler.
+ * `subscribe` enables notifications and registers the handler at once
+ * @param characteristic UUID of the characteristic
+ */
+export async function enableNotifications(characteristic: GattUuid) {
+  await invoke('plugin:blec|enable_notifications', {
+    characteristic
+  })
+}
+
+/**
+ * Disable notifications of a characteristic on the device, registered handlers are kept
+ * @param characteristic UUID of the characteristic
+ */
+export async function disableNotifications(characteristic: GattUuid) {
+  await invoke('plugin:blec|disable_notifications', {
+    characteristic
+  })
+}
+
+/**
+ * Remove the handlers of a characteristic while the device keeps sending notifications.
+ * Useful for stateful devices that must keep notifying
+ * @param characteristic UUID of the characteristic
+ */
+export async function removeListener(characteristic: GattUuid) {
+  await invoke('plugin:blec|remove_listener', {
+    characteristic
+  })
+}
+
+/**
+ * Get the time since the last notification was received on a subscribed characteristic.
+ * Can be used by a watchdog to detect a subscription that silently stopped delivering data
+ * @param characteristic UUID of the subscribed characteristic
+ * @returns The age of the last notification in milliseconds, or null if none was received yet
+ */
+export async function notificationAge(characteristic: GattUuid): Promise<number | null> {
+  return await invoke<number | null>('plugin:blec|notification_age', {
+    characteristic
+  })
+}
+
+/**
+ * Set the key that identifies devices in scan results and when connecting, reported as BleDevice.address.
+ * 'address' is the default, Apple platforms use the platform id instead because they hide the address.
+ * 'nameThenAddress' uses the advertised name and should only be used if the names are unique.
+ * Set it once during setup, changing it forgets all discovered devices.
+ * Tags and remembered devices are stored by key and are not migrated when the key changes
+ * @param key Key to use for devices
+ */
+export async function setDeviceKey(key: 'address' | 'id' | 'nameThenAddress') {
+  await invoke('plugin:blec|set_device_key', {
+    key
+  })
+}
+
+/**
+ * Retry enabling notifications after transient failures, e.g. GATT 133 on Android right after connecting.
+ * Applies to all subscriptions, defaults to no retries
+ * @param retries Number of retries before subscribing fails
+ * @param delayMs Time to wait before each retry in milliseconds
+ */
+export async function setSubscribeRetries(retries: number, delayMs: number) {
+  await invoke('plugin:blec|set_subscribe_retries', {
+    retries,
+    delayMs
+  })
+}
+
+/** Active subscription with its last notification */
+export type SubscriptionInfo = {
+  uuid: string;
+  /** Value of the last notification, before filters are applied */
+  lastValue: number[] | null;
+  /** Time since the last notification in milliseconds, null if none was received yet */
+  lastNotificationMsAgo: number | null;
+};
+
+/**
+ * Get all subscribed characteristics with their last received value.
+ * Useful for a debug panel to check at once which notifications are still arriving
+ */
+export async function subscriptions(): Promise<SubscriptionInfo[]> {
+  return await invoke<SubscriptionInfo[]>('plugin:blec|subscriptions')
+}
+
 /**
  * Subscribe to a BLE characteristic
  * @param characteristic UUID of the characteristic to subscribe to
  * @param handler Callback function that will be called with the data received for every notification
+ * @param bufferSize Number of notifications buffered before new ones are dropped, defaults to 1 and is limited to 1024
+ * @param filter Only notifications matching the filter are sent to the handler
  */
-export async function subscribe(characteristic: string, handler: (data: Uint8Array) => void) {
+export async function subscribe(characteristic: GattUuid, handler: (data: Uint8Array) => void, bufferSize: number | null = null, filter: NotifyFilter | null = null) {
   let onData = new Channel<Uint8Array>()
   onData.onmessage = handler;
   await invoke('plugin:blec|subscribe', {
     characteristic,
-    onData
+    onData,
+    bufferSize,
+    filter
+  })
+}
+
+/**
+ * Watch for devices advertising a service, e.g. a DFU service of a device in bootloader mode.
+ * The handler is called whenever a device starts advertising the service. The adapter keeps scanning until
+ * the returned id is passed to unwatchForService
+ * @param service UUID of the service
+ * @param handler Callback function that is called with the device
+ * @returns Id of the watch
+ */
+export async function watchForService(service: GattUuid, handler: (device: BleDevice) => void): Promise<number> {
+  let onDevice = new Channel<BleDevice>()
+  onDevice.onmessage = handler;
+  return await invoke<number>('plugin:blec|watch_for_service', {
+    service,
+    onDevice
+  })
+}
+
+/**
+ * Stop a watch started with watchForService
+ * @param id Id returned by watchForService
+ */
+export async function unwatchForService(id: number) {
+  await invoke('plugin:blec|unwatch_for_service', {
+    id
+  })
+}
+
+/**
+ * How a protocol splits messages across notifications.
+ * `lengthPrefixed`: each message starts with a header containing the little endian payload length, messages include the header.
+ * `delimited`: each message ends with the given byte, messages don't include it
+ */
+export type Framing =
+  | { lengthPrefixed: { headerLen: number; lengthOffset: number; lengthSize: number } }
+  | { delimited: number }
+
+/**
+ * Subscribe to a BLE characteristic and receive complete messages the device splits across multiple notifications
+ * @param characteristic UUID of the characteristic to subscribe to
+ * @param framing How message boundaries are detected
+ * @param handler Callback function that will be called with every complete message
+ * @param bufferSize Number of messages buffered before new ones are dropped, defaults to 1 and is limited to 1024
+ */
+export async function subscribeReassembled(characteristic: GattUuid, framing: Framing, handler: (data: Uint8Array) => void, bufferSize: number | null = null) {
+  let onData = new Channel<Uint8Array>()
+  onData.onmessage = handler;
+  await invoke('plugin:blec|subscribe_reassembled', {
+    characteristic,
+    framing,
+    onData,
+    bufferSize
+  })
+}
+
+/**
+ * Subscribe to a BLE characteristic, waiting for a connection if needed.
+ * If no device is connected yet, the subscription is applied once the next connection is ready
+ * @param characteristic UUID of the characteristic to subscribe to
+ * @param handler Callback function that will be called with the data received for every notification
+ * @param bufferSize Number of notifications buffered before new ones are dropped, defaults to 1 and is limited to 1024
+ */
+export async function subscribeWhenReady(characteristic: GattUuid, handler: (data: Uint8Array) => void, bufferSize: number | null = null) {
+  let onData = new Channel<Uint8Array>()
+  onData.onmessage = handler;
+  await invoke('plugin:blec|subscribe_when_ready', {
+    characteristic,
+    onData,
+    bufferSize
   })
 }
 
@@ -165,12 +1013,16 @@ export async function subscribe(characteristic: string, handler: (data: Uint8Arr
  * Subscribe to a BLE characteristic. Converts the received data to a string
  * @param characteristic UUID of the characteristic to subscribe to
  * @param handler Callback function that will be called with the data received for every notification
+ * @param bufferSize Number of notifications buffered before new ones are dropped, defaults to 1 and is limited to 1024
+ * @param filter Only notifications matching the filter are sent to the handler
  */
-export async function subscribeString(characteristic: string, handler: (data: string) => void) {
+export async function subscribeString(characteristic: GattUuid, handler: (data: string) => void, bufferSize: number | null = null, filter: NotifyFilter | null = null) {
   let onData = new Channel<string>()
   onData.onmessage = handler;
   await invoke('plugin:blec|subscribe_string', {
     characteristic,
-    onData
+    onData,
+    bufferSize,
+    filter
   })
 }
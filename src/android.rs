@@ -22,12 +22,24 @@ use tauri::{
 };
 use tokio::sync::RwLock;
 use tokio_stream::wrappers::ReceiverStream;
-use tracing::info;
+use tracing::{info, warn};
 use uuid::Uuid;
 
 type Result<T> = std::result::Result<T, btleplug::Error>;
 
 static HANDLE: OnceCell<PluginHandle<Wry>> = OnceCell::new();
+/// settings used by the next [`Adapter::start_scan`]
+static SCAN_SETTINGS: std::sync::Mutex<ScanSettings> = std::sync::Mutex::new(ScanSettings {
+    scan_mode: crate::models::ScanMode::LowPower,
+    extended_advertising: false,
+});
+
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ScanSettings {
+    scan_mode: crate::models::ScanMode,
+    extended_advertising: bool,
+}
 
 fn get_handle() -> &'static PluginHandle<Wry> {
     HANDLE.get().expect("plugin handle not initialized")
@@ -42,6 +54,34 @@ pub fn init<C: serde::de::DeserializeOwned>(
     Ok(())
 }
 
+/// Sets the settings used by the next scan, `start_scan` of the `Central` trait can't take them
+pub fn set_scan_settings(scan_mode: crate::models::ScanMode, extended_advertising: bool) {
+    *SCAN_SETTINGS.lock().expect("scan settings lock poisoned") = ScanSettings {
+        scan_mode,
+        extended_advertising,
+    };
+}
+
+/// Returns a stream of writes that failed after they were handed to the plugin
+pub fn write_errors() -> Result<impl Stream<Item = crate::models::WriteError> + Unpin> {
+    let (tx, rx) = tokio::sync::mpsc::channel(16);
+    let channel: Channel = Channel::new(move |response| {
+        match response.deserialize::<crate::models::WriteError>() {
+            Ok(error) => {
+                if let Err(e) = tx.try_send(error) {
+                    warn!("dropping write error: {e}");
+                }
+            }
+            Err(e) => tracing::error!("failed to deserialize write error: {e:?}"),
+        }
+        Ok(())
+    });
+    get_handle()
+        .run_mobile_plugin("write_errors", channel)
+        .map_err(|e| btleplug::Error::RuntimeError(e.to_string()))?;
+    Ok(ReceiverStream::new(rx))
+}
+
 #[derive(Debug, Clone)]
 pub struct Adapter;
 static DEVICES: Lazy<RwLock<HashMap<PeripheralId, Peripheral>>> =
@@ -76,6 +116,22 @@ pub fn check_permissions() -> std::result::Result<bool, tauri::plugin::mobile::P
     Ok(result.result)
 }
 
+pub fn disconnect_all() -> std::result::Result<(), tauri::plugin::mobile::PluginInvokeError> {
+    get_handle().run_mobile_plugin::<()>("disconnect_all", serde_json::Value::Null)
+}
+
+pub fn request_enable_bluetooth(
+) -> std::result::Result<bool, tauri::plugin::mobile::PluginInvokeError> {
+    let res: BoolResult =
+        get_handle().run_mobile_plugin("request_enable_bluetooth", serde_json::Value::Null)?;
+    Ok(res.result)
+}
+
+pub fn adapter_features(
+) -> std::result::Result<crate::models::AdapterFeatures, tauri::plugin::mobile::PluginInvokeError> {
+    get_handle().run_mobile_plugin("adapter_features", serde_json::Value::Null)
+}
+
 #[allow(dependency_on_unit_never_type_fallback)]
 #[async_trait]
 impl btleplug::api::Central for Adapter {
@@ -85,11 +141,17 @@ impl btleplug::api::Central for Adapter {
         let (tx, rx) = tokio::sync::mpsc::channel::<CentralEvent>(1);
         let stream = ReceiverStream::new(rx);
         let channel: Channel = Channel::new(move |response| {
-            let event = response
-                .deserialize::<CentralEvent>()
-                .expect("failed to deserialize event");
-            tx.blocking_send(event)
-                .expect("failed to send notification");
+            // a malformed event must not stop the delivery of all further events
+            let event = match response.deserialize::<CentralEvent>() {
+                Ok(event) => event,
+                Err(e) => {
+                    tracing::error!("failed to deserialize event, skipping it: {:?}", e);
+                    return Ok(());
+                }
+            };
+            if let Err(e) = tx.blocking_send(event) {
+                tracing::error!("failed to forward event: {e}");
+            }
             Ok(())
         });
         get_handle()
@@ -104,15 +166,19 @@ impl btleplug::api::Central for Adapter {
         struct ScanParams {
             services: Vec<Uuid>,
             on_device: Channel<serde_json::Value>,
+            #[serde(flatten)]
+            settings: ScanSettings,
         }
         DEVICES.write().await.clear();
         let on_device = Channel::new(on_device_callback);
+        let settings = *SCAN_SETTINGS.lock().expect("scan settings lock poisoned");
         get_handle()
             .run_mobile_plugin(
                 "start_scan",
                 ScanParams {
                     services: filter.services,
                     on_device,
+                    settings,
                 },
             )
             .map_err(|e| btleplug::Error::RuntimeError(e.to_string()))?;
@@ -148,7 +214,14 @@ impl btleplug::api::Central for Adapter {
     }
 
     async fn adapter_state(&self) -> Result<CentralState> {
-        todo!()
+        let res: BoolResult = get_handle()
+            .run_mobile_plugin("adapter_state", serde_json::Value::Null)
+            .map_err(|e| btleplug::Error::RuntimeError(e.to_string()))?;
+        Ok(if res.result {
+            CentralState::PoweredOn
+        } else {
+            CentralState::PoweredOff
+        })
     }
 }
 
@@ -180,6 +253,19 @@ where
         .map_err(serde::de::Error::custom)?)
 }
 
+fn deserialize_base64_opt<'a, D>(deserializer: D) -> std::result::Result<Option<Vec<u8>>, D::Error>
+where
+    D: serde::Deserializer<'a>,
+{
+    let s: Option<String> = Option::deserialize(deserializer)?;
+    s.map(|s| {
+        base64::engine::general_purpose::STANDARD
+            .decode(s)
+            .map_err(serde::de::Error::custom)
+    })
+    .transpose()
+}
+
 fn deserialize_base64_map<'a, D, K>(
     deserializer: D,
 ) -> std::result::Result<HashMap<K, Vec<u8>>, D::Error>
@@ -213,53 +299,15 @@ pub struct Peripheral {
     service_data: HashMap<Uuid, Vec<u8>>,
     #[serde(default)]
     services: Vec<Uuid>,
-}
-#[derive(serde::Serialize)]
-#[serde(rename_all = "camelCase")]
-struct ConnectParams {
-    address: BDAddr,
-}
-
-#[derive(serde::Deserialize)]
-struct BoolResult {
-    result: bool,
-}
-
-#[derive(serde::Serialize)]
-#[serde(rename_all = "camelCase")]
-struct ReadParams {
-    address: BDAddr,
-    characteristic: Uuid,
+    #[serde(default, deserialize_with = "deserialize_base64_opt")]
+    raw_advertisement: Option<Vec<u8>>,
+    #[serde(default)]
+    paired: bool,
 }
 
-#[allow(dependency_on_unit_never_type_fallback)]
-#[async_trait::async_trait]
-impl btleplug::api::Peripheral for Peripheral {
-    fn id(&self) -> PeripheralId {
-        self.id.clone()
-    }
-
-    fn address(&self) -> BDAddr {
-        self.address
-    }
-
-    async fn properties(&self) -> Result<Option<PeripheralProperties>> {
-        Ok(Some(PeripheralProperties {
-            address: self.address,
-            local_name: Some(self.name.clone()),
-            rssi: Some(self.rssi),
-            manufacturer_data: self.manufacturer_data.clone(),
-            service_data: self.service_data.clone(),
-            services: self.services.clone(),
-            // TODO: implement the rest
-            // at the moment not used by the handler or BleDevice struct so we can return default values
-            address_type: Default::default(),
-            class: Default::default(),
-            tx_power_level: Default::default(),
-        }))
-    }
-
-    fn services(&self) -> BTreeSet<Service> {
+impl Peripheral {
+    /// Get the services from the plugin, fails if the plugin call fails
+    fn try_services(&self) -> Result<BTreeSet<Service>> {
         #[derive(serde::Deserialize)]
         struct ResCharacteristic {
             uuid: Uuid,
@@ -285,8 +333,7 @@ impl btleplug::api::Peripheral for Peripheral {
                     address: self.address,
                 },
             )
-            .map_err(|e| btleplug::Error::RuntimeError(e.to_string()))
-            .expect("failed to get services");
+            .map_err(|e| btleplug::Error::RuntimeError(e.to_string()))?;
         let mut services = BTreeSet::new();
         for s in res.result {
             let mut characteristics = BTreeSet::new();
@@ -312,7 +359,70 @@ impl btleplug::api::Peripheral for Peripheral {
                 characteristics,
             });
         }
-        services
+        Ok(services)
+    }
+
+    /// Raw advertisement payload as received in the last scan result
+    pub fn raw_advertisement(&self) -> Option<Vec<u8>> {
+        self.raw_advertisement.clone()
+    }
+
+    /// Whether the device was bonded when it was last seen in a scan
+    pub fn is_paired(&self) -> bool {
+        self.paired
+    }
+}
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ConnectParams {
+    address: BDAddr,
+}
+
+#[derive(serde::Deserialize)]
+struct BoolResult {
+    result: bool,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReadParams {
+    address: BDAddr,
+    characteristic: Uuid,
+}
+
+#[allow(dependency_on_unit_never_type_fallback)]
+#[async_trait::async_trait]
+impl btleplug::api::Peripheral for Peripheral {
+    fn id(&self) -> PeripheralId {
+        self.id.clone()
+    }
+
+    fn address(&self) -> BDAddr {
+        self.address
+    }
+
+    async fn properties(&self) -> Result<Option<PeripheralProperties>> {
+        Ok(Some(PeripheralProperties {
+            address: self.address,
+            local_name: Some(self.name.clone()),
+            rssi: Some(self.rssi),
+            manufacturer_data: self.manufacturer_data.clone(),
+            service_data: self.service_data.clone(),
+            services: self.services.clone(),
+            // TODO: implement the rest
+            // at the moment not used by the handler or BleDevice struct so we can return default values
+            address_type: Default::default(),
+            class: Default::default(),
+            tx_power_level: Default::default(),
+        }))
+    }
+
+    fn services(&self) -> BTreeSet<Service> {
+        // the trait doesn't allow errors, use ServicesExt::fetch_services to handle them
+        self.try_services().unwrap_or_else(|e| {
+            warn!("failed to get services: {e}");
+            BTreeSet::new()
+        })
     }
 
     async fn is_connected(&self) -> Result<bool> {
@@ -471,12 +581,46 @@ impl btleplug::api::Peripheral for Peripheral {
         Ok(Box::pin(stream))
     }
 
-    async fn write_descriptor(&self, _descriptor: &Descriptor, _data: &[u8]) -> Result<()> {
-        todo!()
+    async fn write_descriptor(&self, descriptor: &Descriptor, data: &[u8]) -> Result<()> {
+        get_handle()
+            .run_mobile_plugin(
+                "write_descriptor",
+                serde_json::json!({
+                    "address": self.address,
+                    "characteristic": descriptor.characteristic_uuid,
+                    "descriptor": descriptor.uuid,
+                    "data": data,
+                }),
+            )
+            .map_err(|e| btleplug::Error::RuntimeError(e.to_string()))?;
+        Ok(())
     }
 
-    async fn read_descriptor(&self, _descriptor: &Descriptor) -> Result<Vec<u8>> {
-        todo!()
+    async fn read_descriptor(&self, descriptor: &Descriptor) -> Result<Vec<u8>> {
+        #[derive(serde::Deserialize)]
+        struct DescriptorValue {
+            #[serde(deserialize_with = "deserialize_base64")]
+            value: Vec<u8>,
+        }
+        #[derive(serde::Deserialize)]
+        struct DescriptorsResult {
+            result: Vec<DescriptorValue>,
+        }
+        let res: DescriptorsResult = get_handle()
+            .run_mobile_plugin(
+                "read_descriptor",
+                serde_json::json!({
+                    "address": self.address,
+                    "characteristic": descriptor.characteristic_uuid,
+                    "descriptor": descriptor.uuid,
+                }),
+            )
+            .map_err(|e| btleplug::Error::RuntimeError(e.to_string()))?;
+        res.result
+            .into_iter()
+            .next()
+            .map(|d| d.value)
+            .ok_or_else(|| btleplug::Error::RuntimeError("no descriptor value received".into()))
     }
 }
 
@@ -507,3 +651,213 @@ impl MtuExt for Peripheral {
         })
     }
 }
+
+/// Extension trait to read all descriptors of a characteristic with a single plugin call
+pub trait DescriptorExt {
+    /// Read the values of all descriptors of the given characteristic
+    /// The reads are queued on the Kotlin side so only one IPC round trip is needed
+    fn read_descriptors<'a>(
+        &'a self,
+        characteristic: &'a Characteristic,
+    ) -> futures::future::BoxFuture<'a, Result<Vec<(Uuid, Vec<u8>)>>>;
+}
+
+impl DescriptorExt for Peripheral {
+    fn read_descriptors<'a>(
+        &'a self,
+        characteristic: &'a Characteristic,
+    ) -> futures::future::BoxFuture<'a, Result<Vec<(Uuid, Vec<u8>)>>> {
+        Box::pin(async move {
+            #[derive(serde::Deserialize)]
+            struct DescriptorValue {
+                uuid: Uuid,
+                #[serde(deserialize_with = "deserialize_base64")]
+                value: Vec<u8>,
+            }
+            #[derive(serde::Deserialize)]
+            struct DescriptorsResult {
+                result: Vec<DescriptorValue>,
+            }
+            let res: DescriptorsResult = get_handle()
+                .run_mobile_plugin(
+                    "read_descriptors",
+                    ReadParams {
+                        address: self.address,
+                        characteristic: characteristic.uuid,
+                    },
+                )
+                .map_err(|e| btleplug::Error::RuntimeError(e.to_string()))?;
+            Ok(res.result.into_iter().map(|d| (d.uuid, d.value)).collect())
+        })
+    }
+}
+
+/// number of attempts to get the services from the plugin
+const SERVICES_ATTEMPTS: u32 = 3;
+/// delay between two attempts to get the services
+const SERVICES_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Extension trait to get the services with error handling
+pub trait ServicesExt {
+    /// Get the services of the peripheral, retrying a few times if the plugin call fails,
+    /// e.g. because the discovery has not finished yet
+    fn fetch_services(&self) -> futures::future::BoxFuture<'_, Result<BTreeSet<Service>>>;
+}
+
+impl ServicesExt for Peripheral {
+    fn fetch_services(&self) -> futures::future::BoxFuture<'_, Result<BTreeSet<Service>>> {
+        Box::pin(async move {
+            let mut attempt = 1;
+            loop {
+                match self.try_services() {
+                    Ok(services) => return Ok(services),
+                    Err(e) if attempt < SERVICES_ATTEMPTS => {
+                        warn!("failed to get services (attempt {attempt}), retrying: {e}");
+                        attempt += 1;
+                        tokio::time::sleep(SERVICES_RETRY_DELAY).await;
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+        })
+    }
+}
+
+/// Extension trait to connect using a specific transport
+pub trait ConnectExt {
+    /// Connect to the peripheral using the given transport
+    fn connect_with_transport(
+        &self,
+        transport: crate::models::Transport,
+    ) -> futures::future::BoxFuture<'_, Result<()>>;
+}
+
+impl ConnectExt for Peripheral {
+    fn connect_with_transport(
+        &self,
+        transport: crate::models::Transport,
+    ) -> futures::future::BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            // values of the BluetoothDevice.TRANSPORT_* constants
+            let transport = match transport {
+                crate::models::Transport::Auto => 0,
+                crate::models::Transport::BrEdr => 1,
+                crate::models::Transport::Le => 2,
+            };
+            get_handle()
+                .run_mobile_plugin(
+                    "connect",
+                    serde_json::json!({
+                        "address": self.address,
+                        "transport": transport,
+                    }),
+                )
+                .map_err(|e| btleplug::Error::RuntimeError(e.to_string()))?;
+            Ok(())
+        })
+    }
+}
+
+/// Extension trait to access characteristics by their ATT handle instead of the UUID
+pub trait HandleExt {
+    /// Write to the characteristic with the given value handle
+    fn write_by_handle<'a>(
+        &'a self,
+        handle: u16,
+        data: &'a [u8],
+        write_type: WriteType,
+    ) -> futures::future::BoxFuture<'a, Result<()>>;
+    /// Read the characteristic with the given value handle
+    fn read_by_handle(&self, handle: u16) -> futures::future::BoxFuture<'_, Result<Vec<u8>>>;
+}
+
+impl HandleExt for Peripheral {
+    fn write_by_handle<'a>(
+        &'a self,
+        handle: u16,
+        data: &'a [u8],
+        write_type: WriteType,
+    ) -> futures::future::BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            get_handle()
+                .run_mobile_plugin(
+                    "write_by_handle",
+                    serde_json::json!({
+                        "address": self.address,
+                        "handle": handle,
+                        "data": data,
+                        "withResponse": matches!(write_type, WriteType::WithResponse),
+                    }),
+                )
+                .map_err(|e| btleplug::Error::RuntimeError(e.to_string()))?;
+            Ok(())
+        })
+    }
+
+    fn read_by_handle(&self, handle: u16) -> futures::future::BoxFuture<'_, Result<Vec<u8>>> {
+        Box::pin(async move {
+            #[derive(serde::Deserialize)]
+            struct ReadResult {
+                #[serde(deserialize_with = "deserialize_base64")]
+                value: Vec<u8>,
+            }
+            let res: ReadResult = get_handle()
+                .run_mobile_plugin(
+                    "read_by_handle",
+                    serde_json::json!({
+                        "address": self.address,
+                        "handle": handle,
+                    }),
+                )
+                .map_err(|e| btleplug::Error::RuntimeError(e.to_string()))?;
+            Ok(res.value)
+        })
+    }
+}
+
+/// Link parameters read from the connected peripheral
+pub struct Link {
+    pub tx_phy: Option<crate::models::Phy>,
+    pub rx_phy: Option<crate::models::Phy>,
+    pub rssi: Option<i16>,
+}
+
+/// Extension trait to read the parameters of the current connection
+pub trait LinkExt {
+    /// Read the PHY and RSSI of the connection
+    fn read_link(&self) -> futures::future::BoxFuture<'_, Result<Link>>;
+}
+
+impl LinkExt for Peripheral {
+    fn read_link(&self) -> futures::future::BoxFuture<'_, Result<Link>> {
+        Box::pin(async move {
+            #[derive(serde::Deserialize)]
+            #[serde(rename_all = "camelCase")]
+            struct LinkResult {
+                tx_phy: Option<u8>,
+                rx_phy: Option<u8>,
+                rssi: Option<i16>,
+            }
+            // values of the BluetoothDevice.PHY_LE_* constants
+            let phy = |phy| match phy {
+                1 => Some(crate::models::Phy::Le1M),
+                2 => Some(crate::models::Phy::Le2M),
+                3 => Some(crate::models::Phy::LeCoded),
+                _ => None,
+            };
+            let res: LinkResult = get_handle()
+                .run_mobile_plugin(
+                    "link_info",
+                    serde_json::json!({
+                        "address": self.address,
+                    }),
+                )
+                .map_err(|e| btleplug::Error::RuntimeError(e.to_string()))?;
+            Ok(Link {
+                tx_phy: res.tx_phy.and_then(phy),
+                rx_phy: res.rx_phy.and_then(phy),
+                rssi: res.rssi,
+            })
+        })
+    }
+}
@@ -1,18 +1,30 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use base64::Engine;
 use tauri::ipc::Channel;
 use tauri::{async_runtime, command, AppHandle, Runtime};
 use tokio::sync::mpsc;
-use tracing::info;
+use tracing::{info, warn};
 use uuid::Uuid;
 
 use crate::error::Result;
 use crate::get_handler;
-use crate::models::{BleDevice, ScanFilter, WriteType};
+use crate::models::{
+    AdapterFeatures, BleDevice, CharacteristicInspection, ChunkWritePolicy, ConnectOptions,
+    ConnectProgress, ConnectionStatus, DeviceKey, Endianness, Framing, FromBleBytes, GattTree,
+    GattUuid, LinkInfo, LoopbackStats, NotifyFilter, PreferredConnParams, ReadResult,
+    RememberedDevice, ScanFilter, ScanOptions, Service, SubscriptionInfo, WriteType,
+};
 
 #[command]
 pub(crate) async fn scan<R: Runtime>(
     _app: AppHandle<R>,
     timeout: u64,
     on_devices: Channel<Vec<BleDevice>>,
+    options: Option<ScanOptions>,
+    filter: Option<ScanFilter>,
 ) -> Result<()> {
     tracing::info!("Scanning for BLE devices");
     let handler = get_handler()?;
@@ -25,7 +37,41 @@ pub(crate) async fn scan<R: Runtime>(
         }
     });
     handler
-        .discover(Some(tx), timeout, ScanFilter::None)
+        .discover_with_options(
+            Some(tx),
+            timeout,
+            filter.unwrap_or_default(),
+            options.unwrap_or_default(),
+        )
+        .await?;
+    Ok(())
+}
+
+#[command]
+pub(crate) async fn scan_per_device<R: Runtime>(
+    _app: AppHandle<R>,
+    timeout: u64,
+    on_device: Channel<BleDevice>,
+    options: Option<ScanOptions>,
+    filter: Option<ScanFilter>,
+) -> Result<()> {
+    tracing::info!("Scanning for BLE devices one by one");
+    let handler = get_handler()?;
+    let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+    async_runtime::spawn(async move {
+        while let Some(device) = rx.recv().await {
+            on_device
+                .send(device)
+                .expect("failed to send device to the front-end");
+        }
+    });
+    handler
+        .discover_per_device(
+            tx,
+            timeout,
+            filter.unwrap_or_default(),
+            options.unwrap_or_default(),
+        )
         .await?;
     Ok(())
 }
@@ -43,6 +89,8 @@ pub(crate) async fn connect<R: Runtime>(
     _app: AppHandle<R>,
     address: String,
     on_disconnect: Channel<()>,
+    options: Option<ConnectOptions>,
+    on_progress: Channel<ConnectProgress>,
 ) -> Result<()> {
     tracing::info!("Connecting to BLE device: {:?}", address);
     let handler = get_handler()?;
@@ -51,7 +99,22 @@ pub(crate) async fn connect<R: Runtime>(
             .send(())
             .expect("failed to send disconnect event to the front-end");
     };
-    handler.connect(&address, disconnct_handler.into()).await?;
+    let (tx, mut rx) = tokio::sync::mpsc::channel(5);
+    async_runtime::spawn(async move {
+        while let Some(stage) = rx.recv().await {
+            on_progress
+                .send(stage)
+                .expect("failed to send connect progress to the front-end");
+        }
+    });
+    handler
+        .connect_with_progress(
+            &address,
+            disconnct_handler.into(),
+            options.unwrap_or_default(),
+            tx,
+        )
+        .await?;
     Ok(())
 }
 
@@ -63,6 +126,41 @@ pub(crate) async fn disconnect<R: Runtime>(_app: AppHandle<R>) -> Result<()> {
     Ok(())
 }
 
+#[command]
+pub(crate) async fn disconnect_all<R: Runtime>(_app: AppHandle<R>) -> Result<()> {
+    tracing::info!("Disconnecting all BLE devices");
+    let handler = get_handler()?;
+    handler.disconnect_all().await?;
+    Ok(())
+}
+
+#[command]
+pub(crate) async fn reset<R: Runtime>(_app: AppHandle<R>) -> Result<()> {
+    tracing::info!("Resetting BLE handler");
+    let handler = get_handler()?;
+    handler.reset().await?;
+    Ok(())
+}
+
+/// forwarding tasks of the state commands, calling a command again replaces its previous task
+/// so re-subscribing frontends don't leak tasks
+static CONNECTION_STATE_TASK: Mutex<Option<async_runtime::JoinHandle<()>>> = Mutex::new(None);
+static CONNECTION_STATUS_TASK: Mutex<Option<async_runtime::JoinHandle<()>>> = Mutex::new(None);
+static SCANNING_STATE_TASK: Mutex<Option<async_runtime::JoinHandle<()>>> = Mutex::new(None);
+static SCAN_COMPLETE_TASK: Mutex<Option<async_runtime::JoinHandle<()>>> = Mutex::new(None);
+
+/// Stores the task in the slot and aborts the task it replaces
+/// Aborting drops the receiver, so the handler removes the matching sender on its next update
+fn replace_task(
+    slot: &Mutex<Option<async_runtime::JoinHandle<()>>>,
+    task: async_runtime::JoinHandle<()>,
+) {
+    let previous = slot.lock().expect("task slot poisoned").replace(task);
+    if let Some(previous) = previous {
+        previous.abort();
+    }
+}
+
 #[command]
 pub(crate) async fn connection_state<R: Runtime>(
     _app: AppHandle<R>,
@@ -74,13 +172,36 @@ pub(crate) async fn connection_state<R: Runtime>(
     update
         .send(handler.is_connected())
         .expect("failed to send connection state");
-    async_runtime::spawn(async move {
+    let task = async_runtime::spawn(async move {
         while let Some(connected) = rx.recv().await {
             update
                 .send(connected)
                 .expect("failed to send connection state to the front-end");
         }
     });
+    replace_task(&CONNECTION_STATE_TASK, task);
+    Ok(())
+}
+
+#[command]
+pub(crate) async fn connection_status<R: Runtime>(
+    _app: AppHandle<R>,
+    update: Channel<ConnectionStatus>,
+) -> Result<()> {
+    let handler = get_handler()?;
+    let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+    handler.set_connection_status_channel(tx).await;
+    update
+        .send(handler.connection_status().await)
+        .expect("failed to send connection status");
+    let task = async_runtime::spawn(async move {
+        while let Some(status) = rx.recv().await {
+            update
+                .send(status)
+                .expect("failed to send connection status to the front-end");
+        }
+    });
+    replace_task(&CONNECTION_STATUS_TASK, task);
     Ok(())
 }
 
@@ -93,44 +214,227 @@ pub(crate) async fn scanning_state<R: Runtime>(
     let (tx, mut rx) = tokio::sync::mpsc::channel(1);
     handler.set_scanning_update_channel(tx).await;
     update
-        .send(handler.is_scanning().await)
+        .send(handler.is_scanning())
         .expect("failed to send scanning state");
-    async_runtime::spawn(async move {
+    let task = async_runtime::spawn(async move {
         while let Some(scanning) = rx.recv().await {
             update
                 .send(scanning)
                 .expect("failed to send scanning state to the front-end");
         }
     });
+    replace_task(&SCANNING_STATE_TASK, task);
+    Ok(())
+}
+
+#[command]
+pub(crate) async fn scan_complete<R: Runtime>(
+    _app: AppHandle<R>,
+    update: Channel<usize>,
+) -> Result<()> {
+    let handler = get_handler()?;
+    let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+    handler.set_scan_complete_channel(tx).await;
+    let task = async_runtime::spawn(async move {
+        while let Some(count) = rx.recv().await {
+            update
+                .send(count)
+                .expect("failed to send scan complete to the front-end");
+        }
+    });
+    replace_task(&SCAN_COMPLETE_TASK, task);
     Ok(())
 }
 
 #[command]
 pub(crate) async fn send<R: Runtime>(
     _app: AppHandle<R>,
-    characteristic: Uuid,
+    characteristic: GattUuid,
     data: Vec<u8>,
-    write_type: WriteType,
+    write_type: Option<WriteType>,
 ) -> Result<()> {
+    let characteristic = Uuid::from(characteristic);
     info!("Sending data: {data:?}");
     let handler = get_handler()?;
+    handler.check_writable(characteristic).await?;
+    let write_type = match write_type {
+        Some(write_type) => write_type,
+        None => handler.default_write_type().await,
+    };
     handler.send_data(characteristic, &data, write_type).await?;
     Ok(())
 }
 
 #[command]
-pub(crate) async fn recv<R: Runtime>(_app: AppHandle<R>, characteristic: Uuid) -> Result<Vec<u8>> {
+pub(crate) async fn supported_write_types<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
+) -> Result<Vec<WriteType>> {
+    let characteristic = Uuid::from(characteristic);
+    let handler = get_handler()?;
+    handler.supported_write_types(characteristic).await
+}
+
+#[command]
+pub(crate) async fn connected_services<R: Runtime>(_app: AppHandle<R>) -> Result<Vec<Service>> {
+    let handler = get_handler()?;
+    handler.connected_services().await
+}
+
+#[command]
+pub(crate) async fn gatt_tree<R: Runtime>(_app: AppHandle<R>) -> Result<GattTree> {
+    let handler = get_handler()?;
+    handler.gatt_tree().await
+}
+
+#[command]
+pub(crate) async fn send_chunked<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
+    data: Vec<u8>,
+    policy: Option<ChunkWritePolicy>,
+) -> Result<()> {
+    let characteristic = Uuid::from(characteristic);
+    info!("Sending {} bytes in chunks", data.len());
+    let handler = get_handler()?;
+    handler.check_writable(characteristic).await?;
+    handler
+        .send_data_chunked(characteristic, &data, policy.unwrap_or_default())
+        .await?;
+    Ok(())
+}
+
+#[command]
+pub(crate) async fn send_with_progress<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
+    data: Vec<u8>,
+    write_type: Option<WriteType>,
+    on_progress: Channel<f32>,
+) -> Result<()> {
+    let characteristic = Uuid::from(characteristic);
+    info!("Sending {} bytes with progress", data.len());
+    let handler = get_handler()?;
+    handler.check_writable(characteristic).await?;
+    let write_type = match write_type {
+        Some(write_type) => write_type,
+        None => handler.default_write_type().await,
+    };
+    let (tx, mut rx) = mpsc::channel(1);
+    async_runtime::spawn(async move {
+        while let Some(progress) = rx.recv().await {
+            if let Err(e) = on_progress.send(progress) {
+                warn!("failed to send progress to the front-end: {e}");
+            }
+        }
+    });
+    handler
+        .send_data_with_progress(characteristic, &data, write_type, tx)
+        .await
+}
+
+#[command]
+pub(crate) async fn recv<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
+) -> Result<Vec<u8>> {
+    let characteristic = Uuid::from(characteristic);
     let handler = get_handler()?;
     let data = handler.recv_data(characteristic).await?;
     Ok(data)
 }
 
+#[command]
+pub(crate) async fn duplicate_characteristics<R: Runtime>(_app: AppHandle<R>) -> Result<Vec<Uuid>> {
+    let handler = get_handler()?;
+    Ok(handler.duplicate_characteristics().await)
+}
+
+#[command]
+pub(crate) async fn read_parallel<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristics: Vec<GattUuid>,
+) -> Result<HashMap<Uuid, ReadResult>> {
+    let characteristics: Vec<Uuid> = characteristics.into_iter().map(Uuid::from).collect();
+    let handler = get_handler()?;
+    let results = handler.read_parallel(&characteristics).await?;
+    Ok(results
+        .into_iter()
+        .map(|(uuid, result)| (uuid, result.into()))
+        .collect())
+}
+
+#[command]
+pub(crate) async fn send_in_service<R: Runtime>(
+    _app: AppHandle<R>,
+    service: GattUuid,
+    characteristic: GattUuid,
+    data: Vec<u8>,
+    write_type: Option<WriteType>,
+) -> Result<()> {
+    let service = Uuid::from(service);
+    let characteristic = Uuid::from(characteristic);
+    let handler = get_handler()?;
+    handler.check_writable(characteristic).await?;
+    let write_type = match write_type {
+        Some(write_type) => write_type,
+        None => handler.default_write_type().await,
+    };
+    handler
+        .send_data_in_service(service, characteristic, &data, write_type)
+        .await
+}
+
+#[command]
+pub(crate) async fn recv_in_service<R: Runtime>(
+    _app: AppHandle<R>,
+    service: GattUuid,
+    characteristic: GattUuid,
+) -> Result<Vec<u8>> {
+    let service = Uuid::from(service);
+    let characteristic = Uuid::from(characteristic);
+    let handler = get_handler()?;
+    handler.recv_data_in_service(service, characteristic).await
+}
+
+#[command]
+pub(crate) async fn read_service<R: Runtime>(
+    _app: AppHandle<R>,
+    service: GattUuid,
+) -> Result<HashMap<Uuid, Vec<u8>>> {
+    let service = Uuid::from(service);
+    let handler = get_handler()?;
+    handler.read_service(service).await
+}
+
+#[command]
+pub(crate) async fn send_by_handle<R: Runtime>(
+    _app: AppHandle<R>,
+    handle: u16,
+    data: Vec<u8>,
+    write_type: Option<WriteType>,
+) -> Result<()> {
+    let handler = get_handler()?;
+    handler.check_writable_handle(handle).await?;
+    let write_type = match write_type {
+        Some(write_type) => write_type,
+        None => handler.default_write_type().await,
+    };
+    handler.write_by_handle(handle, &data, write_type).await
+}
+
+#[command]
+pub(crate) async fn recv_by_handle<R: Runtime>(_app: AppHandle<R>, handle: u16) -> Result<Vec<u8>> {
+    let handler = get_handler()?;
+    handler.recv_by_handle(handle).await
+}
+
 #[command]
 pub(crate) async fn send_string<R: Runtime>(
     app: AppHandle<R>,
-    characteristic: Uuid,
+    characteristic: GattUuid,
     data: String,
-    write_type: WriteType,
+    write_type: Option<WriteType>,
 ) -> Result<()> {
     let data = data.as_bytes().to_vec();
     send(app, characteristic, data, write_type).await
@@ -139,31 +443,274 @@ pub(crate) async fn send_string<R: Runtime>(
 #[command]
 pub(crate) async fn recv_string<R: Runtime>(
     app: AppHandle<R>,
-    characteristic: Uuid,
+    characteristic: GattUuid,
 ) -> Result<String> {
     let data = recv(app, characteristic).await?;
     Ok(String::from_utf8(data).expect("failed to convert data to string"))
 }
 
-async fn subscribe_channel(characteristic: Uuid) -> Result<mpsc::Receiver<Vec<u8>>> {
+#[command]
+pub(crate) async fn send_base64<R: Runtime>(
+    app: AppHandle<R>,
+    characteristic: GattUuid,
+    data: String,
+    write_type: Option<WriteType>,
+) -> Result<()> {
+    let data = base64::engine::general_purpose::STANDARD.decode(data)?;
+    send(app, characteristic, data, write_type).await
+}
+
+#[command]
+pub(crate) async fn recv_base64<R: Runtime>(
+    app: AppHandle<R>,
+    characteristic: GattUuid,
+) -> Result<String> {
+    let data = recv(app, characteristic).await?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(data))
+}
+
+#[command]
+pub(crate) async fn loopback_test<R: Runtime>(
+    _app: AppHandle<R>,
+    write_characteristic: GattUuid,
+    echo_characteristic: GattUuid,
+    size: usize,
+) -> Result<LoopbackStats> {
+    let write_characteristic = Uuid::from(write_characteristic);
+    let echo_characteristic = Uuid::from(echo_characteristic);
     let handler = get_handler()?;
-    let (tx, rx) = tokio::sync::mpsc::channel(1);
-    handler
-        .subscribe(characteristic, move |data: Vec<u8>| {
-            info!("subscribe_channel: {:?}", data);
-            tx.try_send(data)
-                .expect("failed to send data to the channel");
-        })
+    handler.check_writable(write_characteristic).await?;
+    let stats = handler
+        .loopback_test(write_characteristic, echo_characteristic, size)
         .await?;
+    info!("loopback test finished: {stats:?}");
+    Ok(stats)
+}
+
+async fn recv_number<T: FromBleBytes>(characteristic: Uuid, endianness: Endianness) -> Result<T> {
+    let handler = get_handler()?;
+    handler.recv_as(characteristic, endianness).await
+}
+
+#[command]
+pub(crate) async fn recv_u8<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
+) -> Result<u8> {
+    let characteristic = Uuid::from(characteristic);
+    recv_number(characteristic, Endianness::Little).await
+}
+
+#[command]
+pub(crate) async fn recv_i8<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
+) -> Result<i8> {
+    let characteristic = Uuid::from(characteristic);
+    recv_number(characteristic, Endianness::Little).await
+}
+
+#[command]
+pub(crate) async fn recv_u16_le<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
+) -> Result<u16> {
+    let characteristic = Uuid::from(characteristic);
+    recv_number(characteristic, Endianness::Little).await
+}
+
+#[command]
+pub(crate) async fn recv_u16_be<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
+) -> Result<u16> {
+    let characteristic = Uuid::from(characteristic);
+    recv_number(characteristic, Endianness::Big).await
+}
+
+#[command]
+pub(crate) async fn recv_i16_le<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
+) -> Result<i16> {
+    let characteristic = Uuid::from(characteristic);
+    recv_number(characteristic, Endianness::Little).await
+}
+
+#[command]
+pub(crate) async fn recv_i16_be<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
+) -> Result<i16> {
+    let characteristic = Uuid::from(characteristic);
+    recv_number(characteristic, Endianness::Big).await
+}
+
+#[command]
+pub(crate) async fn recv_u32_le<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
+) -> Result<u32> {
+    let characteristic = Uuid::from(characteristic);
+    recv_number(characteristic, Endianness::Little).await
+}
+
+#[command]
+pub(crate) async fn recv_u32_be<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
+) -> Result<u32> {
+    let characteristic = Uuid::from(characteristic);
+    recv_number(characteristic, Endianness::Big).await
+}
+
+#[command]
+pub(crate) async fn recv_i32_le<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
+) -> Result<i32> {
+    let characteristic = Uuid::from(characteristic);
+    recv_number(characteristic, Endianness::Little).await
+}
+
+#[command]
+pub(crate) async fn recv_i32_be<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
+) -> Result<i32> {
+    let characteristic = Uuid::from(characteristic);
+    recv_number(characteristic, Endianness::Big).await
+}
+
+/// default number of notifications buffered before new ones are dropped
+const DEFAULT_NOTIFY_BUFFER: usize = 1;
+/// largest number of buffered notifications the frontend can request, larger sizes are clamped
+const MAX_NOTIFY_BUFFER: usize = 1024;
+
+fn notify_buffer_size(buffer_size: Option<usize>) -> usize {
+    buffer_size
+        .unwrap_or(DEFAULT_NOTIFY_BUFFER)
+        .clamp(1, MAX_NOTIFY_BUFFER)
+}
+
+async fn subscribe_channel(
+    characteristic: Uuid,
+    buffer_size: Option<usize>,
+    filter: Option<NotifyFilter>,
+) -> Result<mpsc::Receiver<Vec<u8>>> {
+    let handler = get_handler()?;
+    let buffer_size = notify_buffer_size(buffer_size);
+    let (tx, rx) = tokio::sync::mpsc::channel(buffer_size);
+    let callback = move |data: Vec<u8>| {
+        info!("subscribe_channel: {:?}", data);
+        if let Err(e) = tx.try_send(data) {
+            warn!("dropping notification for {characteristic}: {e}");
+        }
+    };
+    if let Some(filter) = filter {
+        handler
+            .subscribe_filtered(characteristic, move |data| filter.matches(data), callback)
+            .await?;
+    } else {
+        handler.subscribe(characteristic, callback).await?;
+    }
     Ok(rx)
 }
 #[command]
 pub(crate) async fn subscribe<R: Runtime>(
     _app: AppHandle<R>,
-    characteristic: Uuid,
+    characteristic: GattUuid,
+    on_data: Channel<Vec<u8>>,
+    buffer_size: Option<usize>,
+    filter: Option<NotifyFilter>,
+) -> Result<()> {
+    let characteristic = Uuid::from(characteristic);
+    let mut rx = subscribe_channel(characteristic, buffer_size, filter).await?;
+    async_runtime::spawn(async move {
+        while let Some(data) = rx.recv().await {
+            on_data
+                .send(data)
+                .expect("failed to send data to the front-end");
+        }
+    });
+    Ok(())
+}
+
+#[command]
+pub(crate) async fn watch_for_service<R: Runtime>(
+    _app: AppHandle<R>,
+    service: GattUuid,
+    on_device: Channel<BleDevice>,
+) -> Result<u64> {
+    let service = Uuid::from(service);
+    let handler = get_handler()?;
+    let (tx, mut rx) = mpsc::channel(1);
+    let id = handler.watch_for_service(service, tx).await?;
+    async_runtime::spawn(async move {
+        while let Some(device) = rx.recv().await {
+            // stop watching once the frontend is gone
+            if on_device.send(device).is_err() {
+                break;
+            }
+        }
+    });
+    Ok(id)
+}
+
+#[command]
+pub(crate) async fn unwatch_for_service<R: Runtime>(_app: AppHandle<R>, id: u64) -> Result<()> {
+    let handler = get_handler()?;
+    handler.unwatch_for_service(id).await;
+    Ok(())
+}
+
+#[command]
+pub(crate) async fn subscribe_reassembled<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
+    framing: Framing,
+    on_data: Channel<Vec<u8>>,
+    buffer_size: Option<usize>,
+) -> Result<()> {
+    let characteristic = Uuid::from(characteristic);
+    let handler = get_handler()?;
+    let buffer_size = notify_buffer_size(buffer_size);
+    let (tx, mut rx) = tokio::sync::mpsc::channel(buffer_size);
+    handler
+        .subscribe_reassembled(characteristic, framing, move |data: Vec<u8>| {
+            if let Err(e) = tx.try_send(data) {
+                warn!("dropping message for {characteristic}: {e}");
+            }
+        })
+        .await?;
+    async_runtime::spawn(async move {
+        while let Some(data) = rx.recv().await {
+            on_data
+                .send(data)
+                .expect("failed to send data to the front-end");
+        }
+    });
+    Ok(())
+}
+
+#[command]
+pub(crate) async fn subscribe_when_ready<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
     on_data: Channel<Vec<u8>>,
+    buffer_size: Option<usize>,
 ) -> Result<()> {
-    let mut rx = subscribe_channel(characteristic).await?;
+    let characteristic = Uuid::from(characteristic);
+    let handler = get_handler()?;
+    let buffer_size = notify_buffer_size(buffer_size);
+    let (tx, mut rx) = tokio::sync::mpsc::channel(buffer_size);
+    handler
+        .subscribe_when_ready(characteristic, move |data: Vec<u8>| {
+            if let Err(e) = tx.try_send(data) {
+                warn!("dropping notification for {characteristic}: {e}");
+            }
+        })
+        .await?;
     async_runtime::spawn(async move {
         while let Some(data) = rx.recv().await {
             on_data
@@ -177,10 +724,13 @@ pub(crate) async fn subscribe<R: Runtime>(
 #[command]
 pub(crate) async fn subscribe_string<R: Runtime>(
     _app: AppHandle<R>,
-    characteristic: Uuid,
+    characteristic: GattUuid,
     on_data: Channel<String>,
+    buffer_size: Option<usize>,
+    filter: Option<NotifyFilter>,
 ) -> Result<()> {
-    let mut rx = subscribe_channel(characteristic).await?;
+    let characteristic = Uuid::from(characteristic);
+    let mut rx = subscribe_channel(characteristic, buffer_size, filter).await?;
     async_runtime::spawn(async move {
         while let Some(data) = rx.recv().await {
             info!("subscribe_string: {:?}", data);
@@ -196,13 +746,265 @@ pub(crate) async fn subscribe_string<R: Runtime>(
 #[command]
 pub(crate) async fn unsubscribe<R: Runtime>(
     _app: AppHandle<R>,
-    characteristic: Uuid,
+    characteristic: GattUuid,
 ) -> Result<()> {
+    let characteristic = Uuid::from(characteristic);
     let handler = get_handler()?;
     handler.unsubscribe(characteristic).await?;
     Ok(())
 }
 
+#[command]
+pub(crate) async fn enable_notifications<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
+) -> Result<()> {
+    let characteristic = Uuid::from(characteristic);
+    let handler = get_handler()?;
+    handler.enable_notifications(characteristic).await
+}
+
+#[command]
+pub(crate) async fn disable_notifications<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
+) -> Result<()> {
+    let characteristic = Uuid::from(characteristic);
+    let handler = get_handler()?;
+    handler.disable_notifications(characteristic).await
+}
+
+#[command]
+pub(crate) async fn remove_listener<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
+) -> Result<()> {
+    let characteristic = Uuid::from(characteristic);
+    let handler = get_handler()?;
+    handler.remove_listener(characteristic).await
+}
+
+#[command]
+pub(crate) async fn pause_notifications<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
+) -> Result<()> {
+    let characteristic = Uuid::from(characteristic);
+    let handler = get_handler()?;
+    handler.pause_notifications(characteristic).await?;
+    Ok(())
+}
+
+#[command]
+pub(crate) async fn resume_notifications<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
+) -> Result<()> {
+    let characteristic = Uuid::from(characteristic);
+    let handler = get_handler()?;
+    handler.resume_notifications(characteristic).await?;
+    Ok(())
+}
+
+#[command]
+pub(crate) async fn notification_age<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
+) -> Result<Option<u64>> {
+    let characteristic = Uuid::from(characteristic);
+    let handler = get_handler()?;
+    let age = handler.notification_age(characteristic).await;
+    Ok(age.map(|age| u64::try_from(age.as_millis()).unwrap_or(u64::MAX)))
+}
+
+#[command]
+pub(crate) async fn set_device_key<R: Runtime>(_app: AppHandle<R>, key: DeviceKey) -> Result<()> {
+    let handler = get_handler()?;
+    handler.set_device_key(key).await;
+    Ok(())
+}
+
+#[command]
+pub(crate) async fn set_subscribe_retries<R: Runtime>(
+    _app: AppHandle<R>,
+    retries: u32,
+    delay_ms: u64,
+) -> Result<()> {
+    let handler = get_handler()?;
+    handler
+        .set_subscribe_retries(retries, Duration::from_millis(delay_ms))
+        .await;
+    Ok(())
+}
+
+#[command]
+pub(crate) async fn subscriptions<R: Runtime>(_app: AppHandle<R>) -> Result<Vec<SubscriptionInfo>> {
+    let handler = get_handler()?;
+    Ok(handler.subscriptions().await)
+}
+
+#[command]
+pub(crate) async fn read_descriptor<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
+    descriptor: GattUuid,
+) -> Result<Vec<u8>> {
+    let characteristic = Uuid::from(characteristic);
+    let descriptor = Uuid::from(descriptor);
+    let handler = get_handler()?;
+    handler.read_descriptor(characteristic, descriptor).await
+}
+
+#[command]
+pub(crate) async fn inspect_characteristic<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
+) -> Result<CharacteristicInspection> {
+    let characteristic = Uuid::from(characteristic);
+    let handler = get_handler()?;
+    handler.inspect_characteristic(characteristic).await
+}
+
+#[command]
+pub(crate) async fn write_descriptor<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
+    descriptor: GattUuid,
+    data: Vec<u8>,
+) -> Result<()> {
+    let characteristic = Uuid::from(characteristic);
+    let descriptor = Uuid::from(descriptor);
+    let handler = get_handler()?;
+    handler.check_writable(characteristic).await?;
+    handler
+        .write_descriptor(characteristic, descriptor, &data)
+        .await
+}
+
+#[command]
+pub(crate) async fn set_keepalive<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
+    interval_ms: u64,
+) -> Result<()> {
+    let characteristic = Uuid::from(characteristic);
+    let handler = get_handler()?;
+    handler
+        .set_keepalive(characteristic, Duration::from_millis(interval_ms))
+        .await
+}
+
+#[command]
+pub(crate) async fn clear_keepalive<R: Runtime>(_app: AppHandle<R>) -> Result<()> {
+    let handler = get_handler()?;
+    handler.clear_keepalive().await;
+    Ok(())
+}
+
+#[command]
+pub(crate) async fn characteristic_description<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
+) -> Result<Option<String>> {
+    let characteristic = Uuid::from(characteristic);
+    let handler = get_handler()?;
+    handler.characteristic_description(characteristic).await
+}
+
+#[command]
+pub(crate) async fn set_characteristic_description<R: Runtime>(
+    _app: AppHandle<R>,
+    characteristic: GattUuid,
+    description: String,
+) -> Result<()> {
+    let characteristic = Uuid::from(characteristic);
+    let handler = get_handler()?;
+    handler.check_writable(characteristic).await?;
+    handler
+        .set_characteristic_description(characteristic, &description)
+        .await
+}
+
+#[command]
+pub(crate) async fn remember_device<R: Runtime>(
+    _app: AppHandle<R>,
+    address: String,
+    tag: Option<String>,
+) -> Result<RememberedDevice> {
+    let handler = get_handler()?;
+    handler.remember_device(&address, tag).await
+}
+
+#[command]
+pub(crate) async fn remembered_devices<R: Runtime>(
+    _app: AppHandle<R>,
+) -> Result<Vec<RememberedDevice>> {
+    let handler = get_handler()?;
+    handler.remembered_devices()
+}
+
+#[command]
+pub(crate) async fn forget_device<R: Runtime>(_app: AppHandle<R>, address: String) -> Result<()> {
+    let handler = get_handler()?;
+    handler.forget_device(&address)
+}
+
+#[command]
+pub(crate) async fn link_info<R: Runtime>(_app: AppHandle<R>) -> Result<LinkInfo> {
+    let handler = get_handler()?;
+    handler.link_info().await
+}
+
+#[command]
+pub(crate) async fn preferred_connection_parameters<R: Runtime>(
+    _app: AppHandle<R>,
+) -> Result<PreferredConnParams> {
+    let handler = get_handler()?;
+    handler.preferred_connection_parameters().await
+}
+
+#[command]
+pub(crate) async fn firmware_revision<R: Runtime>(_app: AppHandle<R>) -> Result<String> {
+    let handler = get_handler()?;
+    handler.firmware_revision().await
+}
+
+#[command]
+pub(crate) async fn get_device<R: Runtime>(
+    _app: AppHandle<R>,
+    address: String,
+) -> Result<Option<BleDevice>> {
+    let handler = get_handler()?;
+    Ok(handler.device(&address).await)
+}
+
+#[command]
+pub(crate) async fn validate_connect_options<R: Runtime>(
+    _app: AppHandle<R>,
+    options: ConnectOptions,
+) -> Result<()> {
+    let handler = get_handler()?;
+    handler.validate_connect_options(&options).await
+}
+
+#[command]
+pub(crate) async fn is_powered<R: Runtime>(_app: AppHandle<R>) -> Result<bool> {
+    let handler = get_handler()?;
+    handler.is_powered().await
+}
+
+#[command]
+pub(crate) async fn request_enable_bluetooth<R: Runtime>(_app: AppHandle<R>) -> Result<bool> {
+    let handler = get_handler()?;
+    handler.request_enable_bluetooth().await
+}
+
+#[command]
+pub(crate) async fn adapter_features<R: Runtime>(_app: AppHandle<R>) -> Result<AdapterFeatures> {
+    let handler = get_handler()?;
+    handler.adapter_features().await
+}
+
 #[command]
 pub(crate) fn check_permissions() -> Result<bool> {
     crate::check_permissions()
@@ -217,22 +1019,90 @@ pub(crate) async fn request_mtu<R: Runtime>(_app: AppHandle<R>, mtu: u16) -> Res
     Ok(agreed_mtu)
 }
 
+#[command]
+pub(crate) async fn max_write_length<R: Runtime>(_app: AppHandle<R>) -> Result<u16> {
+    let handler = get_handler()?;
+    Ok(handler.max_write_length().await)
+}
+
 pub fn commands<R: Runtime>() -> impl Fn(tauri::ipc::Invoke<R>) -> bool {
     tauri::generate_handler![
         scan,
+        scan_per_device,
         stop_scan,
         connect,
         disconnect,
+        disconnect_all,
+        reset,
+        connected_services,
+        gatt_tree,
         connection_state,
+        connection_status,
         send,
         send_string,
+        send_chunked,
         recv,
         recv_string,
+        send_base64,
+        recv_base64,
+        loopback_test,
         subscribe,
         subscribe_string,
         unsubscribe,
+        read_descriptor,
+        inspect_characteristic,
+        write_descriptor,
+        enable_notifications,
+        disable_notifications,
+        remove_listener,
+        pause_notifications,
+        resume_notifications,
+        send_by_handle,
+        recv_by_handle,
+        read_service,
+        read_parallel,
+        duplicate_characteristics,
+        send_in_service,
+        recv_in_service,
+        supported_write_types,
+        subscribe_when_ready,
+        preferred_connection_parameters,
+        firmware_revision,
+        watch_for_service,
+        unwatch_for_service,
+        send_with_progress,
+        characteristic_description,
+        set_characteristic_description,
+        subscribe_reassembled,
+        set_keepalive,
+        clear_keepalive,
+        validate_connect_options,
+        get_device,
+        notification_age,
+        subscriptions,
+        set_subscribe_retries,
+        set_device_key,
         scanning_state,
+        scan_complete,
         check_permissions,
-        request_mtu
+        adapter_features,
+        is_powered,
+        request_enable_bluetooth,
+        link_info,
+        remember_device,
+        remembered_devices,
+        forget_device,
+        request_mtu,
+        max_write_length,
+        recv_u8,
+        recv_i8,
+        recv_u16_le,
+        recv_u16_be,
+        recv_i16_le,
+        recv_i16_be,
+        recv_u32_le,
+        recv_u32_be,
+        recv_i32_le,
+        recv_i32_be
     ]
 }
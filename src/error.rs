@@ -13,12 +13,42 @@ pub enum Error {
     #[error("Characteristic {0} not available")]
     CharacNotAvailable(String),
 
+    #[error("Descriptor {0} not available")]
+    DescriptorNotAvailable(String),
+
+    #[error("Service {0} not available")]
+    ServiceNotAvailable(String),
+
+    #[error("{0} is not supported on this platform")]
+    NotSupported(String),
+
+    #[error("A scan is in progress, stop the scan before connecting")]
+    ScanInProgress,
+
+    #[error("A scan is already running")]
+    ScanAlreadyRunning,
+
+    #[error("Writing to {0} is not permitted")]
+    WriteNotPermitted(String),
+
+    #[error("Characteristic {0} does not support writes")]
+    NotWritable(String),
+
+    #[error("Characteristic {0} does not support {1} writes")]
+    WriteTypeNotSupported(String, String),
+
     #[error("No device connected")]
     NoDeviceConnected,
 
+    #[error("Device disconnected during the operation")]
+    DisconnectedDuringOperation,
+
     #[error("Device is already connected.")]
     AlreadyConnected,
 
+    #[error("Another device is connected: {0}")]
+    AnotherDeviceConnected(String),
+
     #[error("Handler not initialized")]
     HandlerNotInitialized,
 
@@ -34,9 +64,49 @@ pub enum Error {
     #[error("Unknown error during connect")]
     ConnectionFailed,
 
+    #[error("No services or characteristics found on the device")]
+    NoServicesFound,
+
     #[error("Mask must match manufacturer data length")]
     InvalidFilterMask,
 
+    #[error("The length field must be 1 to 8 bytes inside the header")]
+    InvalidFraming,
+
+    #[error("Write {step} of the sequence failed: {source}")]
+    WriteSequenceFailed { step: usize, source: Box<Error> },
+
+    #[error("The echoed value did not match the written data")]
+    VerificationFailed,
+
+    #[error("Timed out waiting for the device")]
+    Timeout,
+
+    #[error("The keepalive interval must be at least {0}ms")]
+    KeepaliveIntervalTooShort(u128),
+
+    #[error("Invalid base64 data: {0}")]
+    InvalidBase64(#[from] base64::DecodeError),
+
+    #[error("Offset {offset} is past the end of the {len} byte value")]
+    InvalidOffset { offset: usize, len: usize },
+
+    #[error("Expected {expected} bytes but received {actual}")]
+    InvalidValueLength { expected: usize, actual: usize },
+
+    #[error("Device storage is not available")]
+    StorageNotAvailable,
+
+    #[error("Failed to access device storage: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid device storage: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[cfg(all(feature = "peripheral", target_os = "linux"))]
+    #[error("BlueZ error: {0}")]
+    Bluer(#[from] bluer::Error),
+
     #[cfg(target_os = "android")]
     #[error(transparent)]
     PluginInvoke(#[from] tauri::plugin::mobile::PluginInvokeError),
@@ -1,15 +1,29 @@
 use crate::error::Error;
-use crate::models::{self, fmt_addr, BleDevice, ScanFilter, Service};
-use btleplug::api::CentralEvent;
-use btleplug::api::{Central, Characteristic, Manager as _, Peripheral as _};
+use crate::models::{
+    self, AdapterFeatures, BleDevice, BleStatusEvent, CharacteristicInspection, ChunkWritePolicy,
+    ConnectOptions, ConnectProgress, ConnectResult, ConnectionStatus, DeviceKey, DisconnectReason,
+    Endianness, ExistingConnectionPolicy, Framing, FromBleBytes, GattTree, LinkInfo, LoopbackStats,
+    PnpId, PreferredConnParams, RememberedDevice, ScanFilter, ScanOptions, Service,
+    SubscriptionInfo,
+};
+use btleplug::api::bleuuid::uuid_from_u16;
+use btleplug::api::{
+    Central, CharPropFlags, Characteristic, Descriptor, Manager as _, Peripheral as _,
+};
+use btleplug::api::{CentralEvent, CentralState};
 use btleplug::platform::PeripheralId;
+use futures::future::{join_all, select, Either};
 use futures::{Stream, StreamExt};
-use std::collections::HashMap;
-use std::pin::Pin;
+use once_cell::sync::OnceCell;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::{pin, Pin};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tauri::async_runtime;
-use tokio::sync::{mpsc, watch, Mutex};
+use tokio::sync::{broadcast, mpsc, oneshot, watch, Mutex};
 use tokio::time::sleep;
 use tracing::{debug, error, info, trace, warn};
 use uuid::Uuid;
@@ -19,35 +33,429 @@ use crate::android::{Adapter, Manager, Peripheral};
 #[cfg(not(target_os = "android"))]
 use btleplug::platform::{Adapter, Manager, Peripheral};
 
+const APPEARANCE_UUID: Uuid = uuid_from_u16(0x2A01);
+const PNP_ID_UUID: Uuid = uuid_from_u16(0x2A50);
+const PREFERRED_CONN_PARAMS_UUID: Uuid = uuid_from_u16(0x2A04);
+const FIRMWARE_REVISION_UUID: Uuid = uuid_from_u16(0x2A26);
+const DEVICE_NAME_UUID: Uuid = uuid_from_u16(0x2A00);
+const SERVICE_CHANGED_UUID: Uuid = uuid_from_u16(0x2A05);
+const USER_DESCRIPTION_UUID: Uuid = uuid_from_u16(0x2901);
+/// time allowed to connect and read the name of a device while scanning
+const NAME_RESOLVE_TIMEOUT: Duration = Duration::from_secs(5);
+/// time allowed to connect and check the characteristics of a device while scanning
+const CHARACTERISTIC_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+/// time allowed to connect and discover the services of a device matching a scan filter
+const SERVICE_DISCOVERY_TIMEOUT: Duration = Duration::from_secs(5);
+/// temporary connections a scan opens at the same time to get details that are not advertised
+const MAX_TEMPORARY_CONNECTIONS: usize = 2;
+/// bytes kept while waiting for the rest of a fragmented message
+const MAX_REASSEMBLY_BUFFER: usize = 64 * 1024;
+/// time pending operations get to finish before disconnecting
+const DISCONNECT_FLUSH_TIMEOUT: Duration = Duration::from_secs(2);
+/// ATT MTU every device supports without negotiation
+const DEFAULT_MTU: u16 = 23;
+/// bytes of the ATT header in a write request
+const ATT_WRITE_HEADER: u16 = 3;
+/// time to wait for the disconnect event when resetting the handler
+const RESET_DISCONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+/// shortest interval between two keepalive reads, shorter intervals would flood the link
+const MIN_KEEPALIVE_INTERVAL: Duration = Duration::from_millis(100);
+/// time to wait for the echo in a loopback test
+const LOOPBACK_TIMEOUT: Duration = Duration::from_secs(5);
+/// file in the app data dir that stores the remembered devices
+const REMEMBERED_DEVICES_FILE: &str = "blec_devices.json";
+/// duration in milliseconds of the scans run before connecting to an unknown device
+const PRE_CONNECT_SCAN_TIMEOUT: u64 = 1000;
+
+type NotifyPredicate = Box<dyn Fn(&[u8]) -> bool + Send + Sync>;
+
+static NEXT_LISTENER_ID: AtomicU64 = AtomicU64::new(0);
+
 struct Listener {
+    /// identifies the listener among the listeners of the same characteristic
+    id: u64,
     uuid: Uuid,
     callback: SubscriptionHandler,
+    throttle: Option<Throttle>,
+    filter: Option<NotifyPredicate>,
+    paused: bool,
+    /// latest value received while paused, delivered on resume
+    last_value: Option<Vec<u8>>,
+    /// time of the last notification received from the device, even if filtered or paused
+    last_notification_at: Option<Instant>,
+    /// raw value of the last notification received from the device
+    last_received: Option<Vec<u8>>,
+    /// only deliver values that differ from `last_delivered`
+    only_changed: bool,
+    last_delivered: Option<Vec<u8>>,
+    /// combines fragments into complete messages before they are delivered
+    reassembler: Option<Reassembler>,
+    /// registered internally for a single operation, not a subscription of the app
+    temporary: bool,
+}
+
+impl Listener {
+    fn new(uuid: Uuid, callback: SubscriptionHandler) -> Self {
+        Self {
+            id: NEXT_LISTENER_ID.fetch_add(1, Ordering::Relaxed),
+            uuid,
+            callback,
+            throttle: None,
+            filter: None,
+            paused: false,
+            last_value: None,
+            last_notification_at: None,
+            last_received: None,
+            only_changed: false,
+            last_delivered: None,
+            reassembler: None,
+            temporary: false,
+        }
+    }
+}
+
+/// Receives the notifications of a characteristic through a temporary listener
+/// Other listeners keep receiving the notifications, unlike with a second notification stream,
+/// which replaces the only stream on Android
+struct NotificationProbe {
+    id: u64,
+    uuid: Uuid,
+    /// notifications were enabled for another listener before the probe was started
+    was_subscribed: bool,
+    values: mpsc::UnboundedReceiver<Vec<u8>>,
+}
+
+/// Limits how often a listener callback is called.
+/// Notifications arriving faster are dropped except for the latest one, which is delivered
+/// once the interval has passed. The callbacks run one after another on a task of the
+/// listener, which is stopped when the listener is removed.
+struct Throttle {
+    latest: watch::Sender<Option<Vec<u8>>>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl Throttle {
+    fn new(min_interval: Duration, callback: SubscriptionHandler) -> Self {
+        let (latest, mut rx) = watch::channel(None);
+        let task = tokio::task::spawn(async move {
+            while rx.changed().await.is_ok() {
+                let Some(data) = rx.borrow_and_update().clone() else {
+                    continue;
+                };
+                let started = Instant::now();
+                callback.clone().run(data).await;
+                sleep(min_interval.saturating_sub(started.elapsed())).await;
+            }
+        });
+        Self { latest, task }
+    }
+
+    /// Queues the value, replacing a value that wasn't delivered yet
+    fn push(&self, data: Vec<u8>) {
+        self.latest.send_replace(Some(data));
+    }
+}
+
+impl Drop for Throttle {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Collects notification fragments until a complete message according to the framing arrived
+struct Reassembler {
+    framing: Framing,
+    buffer: Vec<u8>,
+}
+
+impl Reassembler {
+    fn new(framing: Framing) -> Self {
+        Self {
+            framing,
+            buffer: vec![],
+        }
+    }
+
+    /// Adds a fragment and returns the messages it completed
+    fn push(&mut self, fragment: &[u8]) -> Vec<Vec<u8>> {
+        self.buffer.extend_from_slice(fragment);
+        let mut messages = vec![];
+        loop {
+            let end = match self.framing {
+                Framing::Delimited(delimiter) => {
+                    let Some(pos) = self.buffer.iter().position(|b| *b == delimiter) else {
+                        break;
+                    };
+                    let mut message: Vec<u8> = self.buffer.drain(..=pos).collect();
+                    message.pop();
+                    messages.push(message);
+                    continue;
+                }
+                Framing::LengthPrefixed {
+                    header_len,
+                    length_offset,
+                    length_size,
+                } => {
+                    if self.buffer.len() < header_len {
+                        break;
+                    }
+                    let mut length = [0; 8];
+                    length[..length_size]
+                        .copy_from_slice(&self.buffer[length_offset..length_offset + length_size]);
+                    usize::try_from(u64::from_le_bytes(length))
+                        .unwrap_or(usize::MAX)
+                        .saturating_add(header_len)
+                }
+            };
+            if self.buffer.len() < end {
+                break;
+            }
+            messages.push(self.buffer.drain(..end).collect());
+        }
+        if self.buffer.len() > MAX_REASSEMBLY_BUFFER {
+            warn!(
+                "dropping {} bytes without a complete message",
+                self.buffer.len()
+            );
+            self.buffer.clear();
+        }
+        messages
+    }
+}
+
+/// Ends a scan early once a discovered device matches the predicate
+struct StopCondition {
+    predicate: Box<dyn Fn(&BleDevice) -> bool + Send>,
+    found: oneshot::Sender<BleDevice>,
+}
+
+/// Clears the scanning flag when a scan task ends without reporting it, e.g. because it panicked
+/// or was aborted
+struct ScanningGuard {
+    handler: &'static Handler,
+    generation: u64,
+}
+
+impl Drop for ScanningGuard {
+    fn drop(&mut self) {
+        // a newer scan owns the flag once it started
+        let _ = self.handler.scanning.compare_exchange(
+            self.generation,
+            0,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        );
+    }
+}
+
+/// Decides which devices are sent to the scan channel based on the [`ScanOptions`]
+struct ScanUpdates {
+    options: ScanOptions,
+    sent: HashMap<String, BleDevice>,
+    last_update: Option<Instant>,
+}
+
+impl ScanUpdates {
+    fn new(options: ScanOptions) -> Self {
+        Self {
+            options,
+            sent: HashMap::new(),
+            last_update: None,
+        }
+    }
+
+    /// Returns the devices to send or `None` if the next update is not due yet
+    fn next(&mut self, devices: &[BleDevice], force: bool) -> Option<Vec<BleDevice>> {
+        if let (Some(interval), Some(last), false) =
+            (self.options.update_interval, self.last_update, force)
+        {
+            if last.elapsed() < Duration::from_millis(interval) {
+                return None;
+            }
+        }
+        let update: Vec<BleDevice> = if self.options.only_changed {
+            devices
+                .iter()
+                .filter(|d| {
+                    self.sent
+                        .get(&d.address)
+                        .map_or(true, |s| device_changed(s, d))
+                })
+                .cloned()
+                .collect()
+        } else {
+            devices.to_vec()
+        };
+        for d in &update {
+            self.sent.insert(d.address.clone(), d.clone());
+        }
+        self.last_update = Some(Instant::now());
+        Some(update)
+    }
+}
+
+fn device_changed(old: &BleDevice, new: &BleDevice) -> bool {
+    old.name != new.name
+        || old.rssi != new.rssi
+        || old.is_connected != new.is_connected
+        || old.services != new.services
+        || old.manufacturer_data != new.manufacturer_data
+        || old.service_data != new.service_data
+        || old.raw_advertisement != new.raw_advertisement
 }
 
 struct HandlerState {
     characs: Vec<Characteristic>,
+    /// services of the connected device as discovered at connect time
+    services: Vec<Service>,
     listen_handle: Option<async_runtime::JoinHandle<()>>,
     on_disconnect: OnDisconnectHandler,
     connection_update_channel: Vec<mpsc::Sender<bool>>,
+    connection_status: ConnectionStatus,
+    connection_status_channel: Vec<mpsc::Sender<ConnectionStatus>>,
     scan_update_channel: Vec<mpsc::Sender<bool>>,
+    scan_complete_channel: Vec<mpsc::Sender<usize>>,
+    any_disconnect_channel: Vec<mpsc::Sender<String>>,
+    write_error_channel: Vec<mpsc::Sender<models::WriteError>>,
     scan_task: Option<tokio::task::JoinHandle<()>>,
+    disconnect_requested: bool,
+    default_write_type: models::WriteType,
+    /// key by which discovered devices are reported and connected
+    device_key: DeviceKey,
+    /// number of retries and delay between them for enabling notifications
+    subscribe_retries: (u32, Duration),
+    /// MTU negotiated with the connected device, if known
+    mtu: Option<u16>,
+    /// user defined tags by address, kept across connections
+    tags: HashMap<String, String>,
+    /// characteristics the frontend commands may write to, `None` allows all
+    writable_allowlist: Option<HashSet<Uuid>>,
+    /// subscriptions applied once the next connection is ready
+    pending_listeners: Vec<Listener>,
+    /// task periodically reading a characteristic to keep the connection active
+    keepalive_task: Option<async_runtime::JoinHandle<()>>,
+    /// incremented for every scan, identifies the scan a [`ScanHandle`] belongs to
+    scan_id: u64,
+    /// ids of the running [`Handler::watch_for_service`] tasks, the adapter keeps scanning
+    /// while this is not empty
+    service_watches: HashSet<u64>,
+    /// incremented for every service watch, identifies it in [`Handler::unwatch_for_service`]
+    watch_id: u64,
 }
 
 impl HandlerState {
+    fn new() -> Self {
+        Self {
+            on_disconnect: OnDisconnectHandler::None,
+            connection_update_channel: vec![],
+            connection_status: ConnectionStatus::Disconnected,
+            connection_status_channel: vec![],
+            scan_task: None,
+            scan_update_channel: vec![],
+            scan_complete_channel: vec![],
+            any_disconnect_channel: vec![],
+            write_error_channel: vec![],
+            listen_handle: None,
+            characs: vec![],
+            services: vec![],
+            disconnect_requested: false,
+            default_write_type: models::WriteType::WithResponse,
+            device_key: DeviceKey::default(),
+            subscribe_retries: (0, Duration::ZERO),
+            mtu: None,
+            tags: HashMap::new(),
+            writable_allowlist: None,
+            pending_listeners: vec![],
+            keepalive_task: None,
+            scan_id: 0,
+            service_watches: HashSet::new(),
+            watch_id: 0,
+        }
+    }
+
+    /// Largest payload of a single write, at least one byte even for an invalid MTU
+    fn max_payload(&self) -> u16 {
+        self.mtu
+            .unwrap_or(DEFAULT_MTU)
+            .saturating_sub(ATT_WRITE_HEADER)
+            .max(1)
+    }
+
+    /// Looks up a characteristic in any service, preferring a primary service if the UUID is
+    /// used more than once
     fn get_charac(&self, uuid: Uuid) -> Result<&Characteristic, Error> {
-        let charac = self.characs.iter().find(|c| c.uuid == uuid);
-        charac.ok_or(Error::CharacNotAvailable(uuid.to_string()))
+        self.find_charac(None, uuid)
+    }
+
+    /// Looks up a characteristic, only in the given service if one is given
+    /// The same characteristic UUID can be used in several services, e.g. a primary and an
+    /// included service. Without a service the characteristic of a primary service is preferred.
+    fn find_charac(&self, service: Option<Uuid>, uuid: Uuid) -> Result<&Characteristic, Error> {
+        let mut matching = self
+            .characs
+            .iter()
+            .filter(|c| c.uuid == uuid && service.map_or(true, |s| c.service_uuid == s));
+        let first = matching
+            .next()
+            .ok_or(Error::CharacNotAvailable(uuid.to_string()))?;
+        let primary = std::iter::once(first)
+            .chain(matching)
+            .find(|c| self.is_primary_service(c.service_uuid));
+        Ok(primary.unwrap_or(first))
+    }
+
+    fn is_primary_service(&self, uuid: Uuid) -> bool {
+        self.services.iter().any(|s| s.uuid == uuid && s.primary)
+    }
+
+    /// Characteristic UUIDs that appear in more than one service
+    fn duplicate_characteristics(&self) -> Vec<Uuid> {
+        let mut seen = HashSet::new();
+        let mut duplicates = vec![];
+        for c in &self.characs {
+            if !seen.insert(c.uuid) && !duplicates.contains(&c.uuid) {
+                duplicates.push(c.uuid);
+            }
+        }
+        duplicates
+    }
+
+    fn get_descriptor(&self, charac: Uuid, uuid: Uuid) -> Result<&Descriptor, Error> {
+        let descriptor = self
+            .get_charac(charac)?
+            .descriptors
+            .iter()
+            .find(|d| d.uuid == uuid);
+        descriptor.ok_or(Error::DescriptorNotAvailable(uuid.to_string()))
     }
 }
 
 pub struct Handler {
     devices: Arc<Mutex<HashMap<String, Peripheral>>>,
-    adapter: Arc<Adapter>,
+    adapter: std::sync::RwLock<Arc<Adapter>>,
+    /// incremented whenever the adapter is replaced by [`Handler::reset`]
+    adapter_generation: watch::Sender<u64>,
+    /// incremented when the connected device disconnects, fails operations still in flight
+    disconnect_generation: watch::Sender<u64>,
     notify_listeners: Arc<Mutex<Vec<Listener>>>,
-    connected_rx: watch::Receiver<bool>,
-    connected_tx: watch::Sender<bool>,
+    /// id of the tracked device while it is connected
+    /// Only events for the tracked device update this, so events of other devices never flip it
+    connected_rx: watch::Receiver<Option<PeripheralId>>,
+    connected_tx: watch::Sender<Option<PeripheralId>>,
+    ready_tx: watch::Sender<bool>,
+    /// generation of the running scan task or 0, readable without locking the state
+    scanning: AtomicU64,
+    /// incremented for every scan, so the [`ScanningGuard`] of a superseded scan leaves the flag alone
+    scan_generation: AtomicU64,
     state: Mutex<HandlerState>,
     connected_dev: Mutex<Option<Peripheral>>,
+    /// copy of `connected_dev` that can be read while an operation holds its lock
+    connected_peripheral: std::sync::RwLock<Option<Peripheral>>,
+    status_tx: broadcast::Sender<BleStatusEvent>,
+    /// peripherals reported by discovery events of the adapter
+    discovered_tx: broadcast::Sender<PeripheralId>,
+    /// app data dir used to store remembered devices
+    data_dir: OnceCell<PathBuf>,
 }
 
 async fn get_central() -> Result<Adapter, Error> {
@@ -57,6 +465,36 @@ async fn get_central() -> Result<Adapter, Error> {
     Ok(central)
 }
 
+/// Handle to a scan started with [`Handler::discover`] or [`Handler::discover_with_options`]
+/// Allows a component to stop the scan it started without stopping a scan started later by
+/// someone else, as [`Handler::stop_scan`] would. Dropping the handle keeps the scan running.
+pub struct ScanHandle {
+    handler: &'static Handler,
+    id: u64,
+}
+
+impl ScanHandle {
+    /// Returns true if this scan is still running
+    pub async fn is_running(&self) -> bool {
+        let state = self.handler.state.lock().await;
+        state.scan_id == self.id
+            && state
+                .scan_task
+                .as_ref()
+                .is_some_and(|handle| !handle.is_finished())
+    }
+
+    /// Stops this scan, does nothing if it already ended or was replaced by a newer scan
+    /// # Errors
+    /// Returns an error if stopping the scan fails
+    pub async fn stop(self) -> Result<(), Error> {
+        if !self.is_running().await {
+            return Ok(());
+        }
+        self.handler.stop_scan().await
+    }
+}
+
 pub enum OnDisconnectHandler {
     None,
     Sync(Box<dyn FnOnce() + Send>),
@@ -124,37 +562,64 @@ impl<F: Fn(Vec<u8>) + Send + Sync + 'static> From<F> for SubscriptionHandler {
 impl Handler {
     pub(crate) async fn new() -> Result<Self, Error> {
         let central = get_central().await?;
-        let (connected_tx, connected_rx) = watch::channel(false);
+        let (connected_tx, connected_rx) = watch::channel(None);
+        let (ready_tx, _) = watch::channel(false);
+        let (status_tx, _) = broadcast::channel(32);
         Ok(Self {
             devices: Arc::new(Mutex::new(HashMap::new())),
-            adapter: Arc::new(central),
+            adapter: std::sync::RwLock::new(Arc::new(central)),
+            adapter_generation: watch::channel(0).0,
+            disconnect_generation: watch::channel(0).0,
             notify_listeners: Arc::new(Mutex::new(vec![])),
             connected_rx,
             connected_tx,
+            ready_tx,
+            scanning: AtomicU64::new(0),
+            scan_generation: AtomicU64::new(0),
             connected_dev: Mutex::new(None),
-            state: Mutex::new(HandlerState {
-                on_disconnect: OnDisconnectHandler::None,
-                connection_update_channel: vec![],
-                scan_task: None,
-                scan_update_channel: vec![],
-                listen_handle: None,
-                characs: vec![],
-            }),
+            connected_peripheral: std::sync::RwLock::new(None),
+            status_tx,
+            discovered_tx: broadcast::channel(64).0,
+            data_dir: OnceCell::new(),
+            state: Mutex::new(HandlerState::new()),
         })
     }
 
+    /// Waits until the plugin is fully initialized and the event loop is listening for adapter events
+    /// Commands issued before this resolves may miss connection or discovery events
+    /// # Example
+    /// ```no_run
+    /// use tauri::async_runtime;
+    /// async_runtime::block_on(async {
+    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///     handler.ready().await;
+    ///     // safe to start scanning now
+    /// });
+    /// ```
+    pub async fn ready(&self) {
+        let mut ready_rx = self.ready_tx.subscribe();
+        // the sender lives as long as the handler so this can't fail
+        let _ = ready_rx.wait_for(|ready| *ready).await;
+    }
+
+    /// Returns true if the plugin is fully initialized, see [`Handler::ready`]
+    pub fn is_ready(&self) -> bool {
+        *self.ready_tx.borrow()
+    }
+
+    pub(crate) fn set_ready(&self) {
+        self.ready_tx.send_replace(true);
+    }
+
     /// Returns true if a device is connected
     pub fn is_connected(&self) -> bool {
-        *self.connected_rx.borrow()
+        self.connected_rx.borrow().is_some()
     }
 
     /// Returns true if the adapter is scanning
-    pub async fn is_scanning(&self) -> bool {
-        if let Some(handle) = &self.state.lock().await.scan_task {
-            !handle.is_finished()
-        } else {
-            false
-        }
+    /// This doesn't lock the handler state, so it is cheap to poll while a scan is running
+    pub fn is_scanning(&self) -> bool {
+        self.scanning.load(Ordering::Acquire) != 0
     }
 
     /// Takes a sender that will be used to send changes in the scanning status
@@ -175,6 +640,70 @@ impl Handler {
         self.state.lock().await.scan_update_channel.push(tx);
     }
 
+    /// Takes a sender that will be notified when a scan ends because its timeout elapsed
+    /// The number of devices found during the scan is sent.
+    /// Scans that are stopped early, e.g. by [`Handler::stop_scan`], don't send a notification.
+    /// # Example
+    /// ```no_run
+    /// use tauri::async_runtime;
+    /// use tokio::sync::mpsc;
+    /// async_runtime::block_on(async {
+    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///     let (tx, mut rx) = mpsc::channel(1);
+    ///     handler.set_scan_complete_channel(tx).await;
+    ///     while let Some(count) = rx.recv().await {
+    ///         println!("Scan complete, {count} devices found");
+    ///     }
+    /// });
+    /// ```
+    pub async fn set_scan_complete_channel(&self, tx: mpsc::Sender<usize>) {
+        self.state.lock().await.scan_complete_channel.push(tx);
+    }
+
+    /// Takes a sender that will receive writes that failed asynchronously on the platform layer
+    /// Writes without response return before the data is transmitted, so a failure to send
+    /// them is not reported by [`Handler::send_data`]. This allows to detect that a burst of
+    /// writes partially failed.
+    /// Only Android reports these failures, other platforms don't expose them.
+    /// # Example
+    /// ```no_run
+    /// use tauri::async_runtime;
+    /// use tokio::sync::mpsc;
+    /// async_runtime::block_on(async {
+    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///     let (tx, mut rx) = mpsc::channel(1);
+    ///     handler.set_write_error_channel(tx).await;
+    ///     while let Some(error) = rx.recv().await {
+    ///         println!("Write to {} failed: {}", error.characteristic, error.message);
+    ///     }
+    /// });
+    /// ```
+    pub async fn set_write_error_channel(&self, tx: mpsc::Sender<models::WriteError>) {
+        self.state.lock().await.write_error_channel.push(tx);
+    }
+
+    /// Takes a sender that will receive the address of every device the adapter reports as
+    /// disconnected, not only the device connected by this handler
+    /// Unlike the `on_disconnect` callback of [`Handler::connect`], this also fires for devices
+    /// connected by other parts of the app or the system, so a supervisor can decide how to
+    /// react to each disconnect.
+    /// # Example
+    /// ```no_run
+    /// use tauri::async_runtime;
+    /// use tokio::sync::mpsc;
+    /// async_runtime::block_on(async {
+    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///     let (tx, mut rx) = mpsc::channel(1);
+    ///     handler.set_any_disconnect_channel(tx).await;
+    ///     while let Some(address) = rx.recv().await {
+    ///         println!("{address} disconnected");
+    ///     }
+    /// });
+    /// ```
+    pub async fn set_any_disconnect_channel(&self, tx: mpsc::Sender<String>) {
+        self.state.lock().await.any_disconnect_channel.push(tx);
+    }
+
     /// Takes a sender that will be used to send changes in the connection status
     /// # Example
     /// ```no_run
@@ -193,13 +722,85 @@ impl Handler {
         self.state.lock().await.connection_update_channel.push(tx);
     }
 
+    /// Returns the detailed connection status
+    pub async fn connection_status(&self) -> ConnectionStatus {
+        self.state.lock().await.connection_status
+    }
+
+    /// Takes a sender that will be used to send changes of the detailed connection status
+    /// In contrast to [`Handler::set_connection_update_channel`] this also reports running
+    /// connection attempts and retries
+    /// # Example
+    /// ```no_run
+    /// use tauri::async_runtime;
+    /// use tokio::sync::mpsc;
+    /// async_runtime::block_on(async {
+    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///     let (tx, mut rx) = mpsc::channel(1);
+    ///     handler.set_connection_status_channel(tx).await;
+    ///     while let Some(status) = rx.recv().await {
+    ///         println!("Connection status: {status:?}");
+    ///     }
+    /// });
+    /// ```
+    pub async fn set_connection_status_channel(&self, tx: mpsc::Sender<ConnectionStatus>) {
+        self.state.lock().await.connection_status_channel.push(tx);
+    }
+
+    /// Returns a stream of status events
+    /// This combines scanning state, discovered devices, connection changes and adapter state
+    /// changes into a single stream.
+    /// Only events emitted after calling this method are received. If the stream is not polled
+    /// fast enough, old events are skipped.
+    /// # Example
+    /// ```no_run
+    /// use futures::StreamExt;
+    /// use tauri::async_runtime;
+    /// async_runtime::block_on(async {
+    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///     let mut events = Box::pin(handler.events());
+    ///     while let Some(event) = events.next().await {
+    ///         println!("Event: {event:?}");
+    ///     }
+    /// });
+    /// ```
+    pub fn events(&self) -> impl Stream<Item = BleStatusEvent> + Send + 'static {
+        futures::stream::unfold(self.status_tx.subscribe(), |mut rx| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) => return Some((event, rx)),
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!("status event stream lagged, skipped {n} events");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+
+    fn send_status_event(&self, event: BleStatusEvent) {
+        // no active receivers is not an error
+        let _ = self.status_tx.send(event);
+    }
+
     /// Connects to the given address
     /// If a callback is provided, it will be called when the device is disconnected.
     /// Because connecting sometimes fails especially on android, this method tries up to 3 times
     /// before returning an error
+    ///
+    /// Returns a [`ConnectResult`] which tells if the services were taken from the platform cache
     /// # Errors
     /// Returns an error if no devices are found, if the device is already connected,
     /// if the connection fails, or if the service/characteristics discovery fails
+    /// Returns [`Error::NoServicesFound`] and disconnects if the device has no characteristics.
+    /// The device is also disconnected if the service discovery fails
+    ///
+    /// A running scan is stopped before connecting, because some platforms fail to connect
+    /// while scanning. Use [`Handler::connect_with_options`] with `stop_scan` disabled to get
+    /// [`Error::ScanInProgress`] instead.
+    ///
+    /// If the device has the Service Changed characteristic, it is subscribed automatically and
+    /// the services are refreshed when it indicates a change, see [`BleStatusEvent::ServicesChanged`]
     /// # Example
     /// ```no_run
     /// use tauri::async_runtime;
@@ -212,19 +813,170 @@ impl Handler {
         &'static self,
         address: &str,
         on_disconnect: OnDisconnectHandler,
-    ) -> Result<(), Error> {
-        if self.devices.lock().await.len() == 0 {
-            self.discover(None, 1000, ScanFilter::None).await?;
+    ) -> Result<ConnectResult, Error> {
+        self.connect_with_options(address, on_disconnect, ConnectOptions::default())
+            .await
+    }
+
+    /// Same as [`Handler::connect`] but allows to configure the connection with [`ConnectOptions`]
+    /// A different device that is still connected is handled according to
+    /// [`ConnectOptions::existing_connection`]
+    /// # Errors
+    /// Returns an error if no devices are found, if the device is already connected,
+    /// if the connection fails, or if the service/characteristics discovery fails
+    /// Returns [`Error::NoServicesFound`] and disconnects if the device has no characteristics
+    /// # Example
+    /// ```no_run
+    /// use tauri::async_runtime;
+    /// use tauri_plugin_blec::models::{ConnectOptions, Transport};
+    /// async_runtime::block_on(async {
+    ///    let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///    let options = ConnectOptions {
+    ///        transport: Transport::Auto,
+    ///        ..Default::default()
+    ///    };
+    ///    handler
+    ///        .connect_with_options("00:00:00:00:00:00", (|| println!("disconnected")).into(), options)
+    ///        .await
+    ///        .unwrap();
+    /// });
+    /// ```
+    pub async fn connect_with_options(
+        &'static self,
+        address: &str,
+        on_disconnect: OnDisconnectHandler,
+        options: ConnectOptions,
+    ) -> Result<ConnectResult, Error> {
+        self.connect_inner(address, on_disconnect, options, None)
+            .await
+    }
+
+    /// Connects to the peripheral with the given id, e.g. from a [`CentralEvent`] of the
+    /// adapter event stream
+    /// The peripheral is looked up on the adapter directly, so no scan is needed if the
+    /// adapter already knows it. Apart from that this behaves like [`Handler::connect_with_options`]
+    /// # Errors
+    /// Returns an error if the adapter doesn't know the peripheral or connecting fails
+    pub async fn connect_by_id(
+        &'static self,
+        id: &PeripheralId,
+        on_disconnect: OnDisconnectHandler,
+        options: ConnectOptions,
+    ) -> Result<ConnectResult, Error> {
+        let peripheral = self.adapter().peripheral(id).await?;
+        let key = self.device_key().await;
+        let address = BleDevice::from_peripheral(&peripheral, key).await?.address;
+        let address = {
+            let mut devices = self.devices.lock().await;
+            let address = known_key(&devices, &peripheral).unwrap_or(address);
+            devices.insert(address.clone(), peripheral);
+            address
+        };
+        self.connect_inner(&address, on_disconnect, options, None)
+            .await
+    }
+
+    /// Same as [`Handler::connect_with_options`] but reports the stages of the connection
+    /// attempt to the given channel
+    /// This allows to show more detailed feedback than a spinner while connecting, because
+    /// service discovery can take multiple seconds.
+    /// # Errors
+    /// Returns an error if no devices are found, if the device is already connected,
+    /// if the connection fails, or if the service/characteristics discovery fails
+    /// Returns [`Error::NoServicesFound`] and disconnects if the device has no characteristics
+    /// # Example
+    /// ```no_run
+    /// use tauri::async_runtime;
+    /// use tauri_plugin_blec::models::ConnectOptions;
+    /// async_runtime::block_on(async {
+    ///    let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///    let (tx, mut rx) = tokio::sync::mpsc::channel(5);
+    ///    async_runtime::spawn(async move {
+    ///        while let Some(stage) = rx.recv().await {
+    ///            println!("{stage:?}");
+    ///        }
+    ///    });
+    ///    handler
+    ///        .connect_with_progress("00:00:00:00:00:00", (|| println!("disconnected")).into(), ConnectOptions::default(), tx)
+    ///        .await
+    ///        .unwrap();
+    /// });
+    /// ```
+    pub async fn connect_with_progress(
+        &'static self,
+        address: &str,
+        on_disconnect: OnDisconnectHandler,
+        options: ConnectOptions,
+        progress: mpsc::Sender<ConnectProgress>,
+    ) -> Result<ConnectResult, Error> {
+        self.connect_inner(address, on_disconnect, options, Some(progress))
+            .await
+    }
+
+    async fn connect_inner(
+        &'static self,
+        address: &str,
+        on_disconnect: OnDisconnectHandler,
+        options: ConnectOptions,
+        progress: Option<mpsc::Sender<ConnectProgress>>,
+    ) -> Result<ConnectResult, Error> {
+        let send_progress = |stage| {
+            let progress = progress.clone();
+            async move {
+                if let Some(tx) = progress {
+                    // a closed receiver only means nobody is interested anymore
+                    let _ = tx.send(stage).await;
+                }
+            }
+        };
+        if !options.stop_scan && self.is_scanning() {
+            return Err(Error::ScanInProgress);
+        }
+        if let Ok(current) = self.connected_device().await {
+            if current.address != address {
+                match options.existing_connection {
+                    ExistingConnectionPolicy::Reject => {
+                        return Err(Error::AnotherDeviceConnected(current.address));
+                    }
+                    ExistingConnectionPolicy::DisconnectExisting => {
+                        debug!(
+                            "disconnecting {} before connecting to {address}",
+                            current.address
+                        );
+                        self.disconnect().await?;
+                    }
+                }
+            }
+        }
+        if self.devices.lock().await.is_empty() {
+            self.discover(None, PRE_CONNECT_SCAN_TIMEOUT, ScanFilter::None)
+                .await?;
         }
         // cancel any running discovery
         let _ = self.stop_scan().await;
+        // scan again if the device was missed, stopping as soon as it shows up
+        let mut retries = options.scan_retries;
+        while retries > 0 && !self.devices.lock().await.contains_key(address) {
+            retries -= 1;
+            debug!("{address} not found, scanning again ({retries} retries left)");
+            let target = address.to_string();
+            self.scan_until(PRE_CONNECT_SCAN_TIMEOUT, ScanFilter::None, move |d| {
+                d.address == target
+            })
+            .await?;
+        }
+        self.set_connection_status(ConnectionStatus::Connecting)
+            .await;
+        send_progress(ConnectProgress::Connecting).await;
         // connect to the given address
         // try up to 3 times before returning an error
         let mut connected = Ok(());
         for i in 0..3 {
-            if let Err(e) = self.connect_device(address).await {
+            if let Err(e) = self.connect_device(address, &options).await {
                 if i < 2 {
                     warn!("Failed to connect device, retrying in 1s: {e}");
+                    self.set_connection_status(ConnectionStatus::Reconnecting)
+                        .await;
                     sleep(Duration::from_secs(1)).await;
                     continue;
                 }
@@ -235,96 +987,317 @@ impl Handler {
             }
         }
         if let Err(e) = connected {
-            *self.connected_dev.lock().await = None;
-            let _ = self.connected_tx.send(false);
+            self.set_connected_dev(None).await;
+            let _ = self.connected_tx.send(None);
+            self.set_connection_status(ConnectionStatus::Disconnected)
+                .await;
             error!("Failed to connect device: {e}");
             return Err(e);
         }
+        self.set_connection_status(ConnectionStatus::Connected)
+            .await;
+        send_progress(ConnectProgress::Connected).await;
         let mut state = self.state.lock().await;
-        // set callback to run on disconnect
+        // set callback to run on disconnect, also if the device disconnects during discovery
         state.on_disconnect = on_disconnect;
         // discover service/characteristics
-        self.connect_services(&mut state).await?;
+        send_progress(ConnectProgress::DiscoveringServices).await;
+        let result = self.connect_services(&mut state).await;
+        let result = match result {
+            Ok(result) => result,
+            Err(e) => {
+                // nothing can be done with the device, so don't leave it connected
+                drop(state);
+                warn!("Service discovery on {address} failed, disconnecting: {e}");
+                if let Err(e) = self.disconnect().await {
+                    error!("Failed to disconnect device without services: {e}");
+                }
+                return Err(e);
+            }
+        };
+        send_progress(ConnectProgress::ServicesDiscovered).await;
         // start background task for notifications
         state.listen_handle = Some(async_runtime::spawn(listen_notify(
             self.connected_dev.lock().await.clone(),
             self.notify_listeners.clone(),
+            self.status_tx.clone(),
         )));
-        Ok(())
+        let pending = std::mem::take(&mut state.pending_listeners);
+        drop(state);
+        if options.reset_notifications {
+            self.disable_all_notifications().await;
+        }
+        self.watch_service_changed().await;
+        for listener in pending {
+            let uuid = listener.uuid;
+            if let Err(e) = self.subscribe_listener(listener).await {
+                warn!("Failed to apply queued subscription to {uuid}: {e}");
+            }
+        }
+        send_progress(ConnectProgress::Ready).await;
+        Ok(result)
     }
 
-    async fn connect_services(&self, state: &mut HandlerState) -> Result<(), Error> {
+    async fn connect_services(&self, state: &mut HandlerState) -> Result<ConnectResult, Error> {
         let device = self.connected_dev.lock().await;
         let device = device.as_ref().ok_or(Error::NoDeviceConnected)?;
-        let mut services = device.services();
-        if services.is_empty() {
+        let mut services = device_services(device).await?;
+        let used_cached_services = !services.is_empty();
+        if !used_cached_services {
             device.discover_services().await?;
-            services = device.services();
+            services = device_services(device).await?;
         }
-        for s in services {
+        for s in &services {
             for c in &s.characteristics {
                 state.characs.push(c.clone());
             }
         }
-        Ok(())
+        state.services = services.iter().map(Service::from).collect();
+        if state.characs.is_empty() {
+            return Err(Error::NoServicesFound);
+        }
+        self.report_duplicate_characteristics(state);
+        Ok(ConnectResult {
+            used_cached_services,
+            service_count: services.len(),
+        })
     }
 
-    async fn connect_device(&self, address: &str) -> Result<(), Error> {
-        debug!("connecting to {address}",);
-        let mut connected_rx = self.connected_rx.clone();
-        let devices = self.devices.lock().await;
-        let device = devices
-            .get(address)
-            .ok_or(Error::UnknownPeripheral(address.to_string()))?;
-        *self.connected_dev.lock().await = Some(device.clone());
-        if device.is_connected().await? {
-            debug!("Device already connected");
-            self.connected_tx
-                .send(true)
-                .expect("failed to send connected update");
-        } else {
-            assert!(
-                !(*connected_rx.borrow_and_update()),
-                "connected_rx is true without device being connected, this is a bug"
-            );
+    /// Runs a new service discovery on the connected device and replaces the known
+    /// services and characteristics
+    /// Returns the number of discovered services
+    /// # Errors
+    /// Returns an error if no device is connected or if the discovery fails
+    pub async fn refresh_services(&self) -> Result<usize, Error> {
+        // don't hold the device lock while waiting for the state, connecting and disconnecting
+        // take them in the opposite order
+        let dev = self
+            .connected_dev
+            .lock()
+            .await
+            .clone()
+            .ok_or(Error::NoDeviceConnected)?;
+        dev.discover_services().await?;
+        let services = device_services(&dev).await?;
+        let mut state = self.state.lock().await;
+        state.characs = services
+            .iter()
+            .flat_map(|s| s.characteristics.iter().cloned())
+            .collect();
+        state.services = services.iter().map(Service::from).collect();
+        self.report_duplicate_characteristics(&state);
+        Ok(services.len())
+    }
+
+    /// Disables notifications and indications of all characteristics that support them,
+    /// failures are only logged
+    async fn disable_all_notifications(&self) {
+        let Some(dev) = self.connected_dev.lock().await.clone() else {
+            return;
+        };
+        let characs: Vec<Characteristic> = self
+            .state
+            .lock()
+            .await
+            .characs
+            .iter()
+            .filter(|c| {
+                c.properties
+                    .intersects(CharPropFlags::NOTIFY | CharPropFlags::INDICATE)
+            })
+            .cloned()
+            .collect();
+        debug!(
+            "disabling notifications of {} characteristics",
+            characs.len()
+        );
+        for c in &characs {
+            if let Err(e) = dev.unsubscribe(c).await {
+                debug!("Failed to disable notifications of {}: {e}", c.uuid);
+            }
+        }
+    }
+
+    fn report_duplicate_characteristics(&self, state: &HandlerState) {
+        let duplicates = state.duplicate_characteristics();
+        if !duplicates.is_empty() {
+            warn!("characteristics used in several services: {duplicates:?}");
+            self.send_status_event(BleStatusEvent::DuplicateCharacteristics(duplicates));
+        }
+    }
+
+    /// Returns the characteristic UUIDs the connected device uses in more than one service
+    /// Methods taking only a characteristic UUID use the characteristic of a primary service
+    /// for these, use the service qualified methods like [`Handler::send_data_in_service`] to
+    /// reach the others. Empty if no device is connected.
+    pub async fn duplicate_characteristics(&self) -> Vec<Uuid> {
+        self.state.lock().await.duplicate_characteristics()
+    }
+
+    /// Subscribes to the Service Changed indication if the device provides it, so the services
+    /// are refreshed when the device changes its GATT database at runtime
+    async fn watch_service_changed(&'static self) {
+        let available = self.state.lock().await.characs.iter().any(|c| {
+            c.uuid == SERVICE_CHANGED_UUID && c.properties.contains(CharPropFlags::INDICATE)
+        });
+        if !available {
+            return;
+        }
+        let callback = SubscriptionHandler::from_async(move |_| {
+            Box::pin(async move {
+                info!("Service Changed indication received, refreshing services");
+                match self.refresh_services().await {
+                    Ok(_) => self.send_status_event(BleStatusEvent::ServicesChanged),
+                    Err(e) => warn!("Failed to refresh services after Service Changed: {e}"),
+                }
+            })
+        });
+        if let Err(e) = self.subscribe(SERVICE_CHANGED_UUID, callback).await {
+            warn!("Failed to subscribe to Service Changed: {e}");
+        }
+    }
+
+    /// Returns the services, characteristics and descriptors of the connected device as a
+    /// nested tree, see [`Handler::connected_services`]
+    /// # Errors
+    /// Returns an error if no device is connected
+    pub async fn gatt_tree(&self) -> Result<GattTree, Error> {
+        Ok(GattTree {
+            services: self.connected_services().await?,
+        })
+    }
+
+    /// Returns the services of the connected device as discovered during connect or the
+    /// last [`Handler::refresh_services`]
+    /// No communication with the device is needed, so this is cheap to call repeatedly
+    /// # Errors
+    /// Returns an error if no device is connected
+    pub async fn connected_services(&self) -> Result<Vec<Service>, Error> {
+        let dev = self.connected_dev.lock().await;
+        dev.as_ref().ok_or(Error::NoDeviceConnected)?;
+        Ok(self.state.lock().await.services.clone())
+    }
+
+    async fn connect_device(&self, address: &str, options: &ConnectOptions) -> Result<(), Error> {
+        debug!("connecting to {address}",);
+        let mut connected_rx = self.connected_rx.clone();
+        let devices = self.devices.lock().await;
+        let device = devices
+            .get(address)
+            .ok_or(Error::UnknownPeripheral(address.to_string()))?;
+        self.set_connected_dev(Some(device.clone())).await;
+        if device.is_connected().await? {
+            debug!("Device already connected");
+            self.connected_tx
+                .send(Some(device.id()))
+                .expect("failed to send connected update");
+        } else {
+            if let Some(id) = connected_rx.borrow_and_update().clone() {
+                // can happen if the adapter reports events out of order, reset the state so a
+                // retry starts clean
+                warn!("connection state still reports {id:?} as connected, resetting");
+                self.connected_tx.send_replace(None);
+                return Err(Error::ConnectionFailed);
+            }
             debug!("Connecting to device");
+            #[cfg(target_os = "android")]
+            {
+                use crate::android::ConnectExt;
+                device.connect_with_transport(options.transport).await?;
+            }
+            #[cfg(not(target_os = "android"))]
             device.connect().await?;
             debug!("waiting for connection event");
             // wait for the actual connection to be established
-            connected_rx
-                .changed()
-                .await
-                .expect("failed to wait for connection event");
+            if let Err(e) = connected_rx.changed().await {
+                error!("failed to wait for connection event: {e}");
+                return Err(Error::ConnectionFailed);
+            }
             debug!("Connecting done");
-            if !*self.connected_rx.borrow() {
+            if self.connected_rx.borrow().as_ref() != Some(&device.id()) {
                 // still not connected
                 return Err(Error::ConnectionFailed);
             }
         }
 
+        let tag = {
+            let mut state = self.state.lock().await;
+            if let Some(tag) = &options.tag {
+                state.tags.insert(address.to_string(), tag.clone());
+            }
+            state.tags.get(address).cloned()
+        };
         self.send_connection_update(true).await;
+        self.send_status_event(BleStatusEvent::Connected {
+            address: address.to_string(),
+            tag,
+        });
         Ok(())
     }
 
+    /// Waits until the operations currently running on the connected device are finished
+    /// Operations on the device run one after the other, so once this returns all writes started
+    /// before, e.g. a burst of writes without response, were handed to the platform.
+    /// # Errors
+    /// Returns [`Error::Timeout`] if the operations don't finish within `timeout`
+    pub async fn flush(&self, timeout: Duration) -> Result<(), Error> {
+        tokio::time::timeout(timeout, self.connected_dev.lock())
+            .await
+            .map(|_| ())
+            .map_err(|_| Error::Timeout)
+    }
+
+    /// Returns the connected device without waiting for the lock of running operations
+    /// Unlike the discovered devices, this isn't cleared by a scan
+    fn connected_peripheral(&self) -> Option<Peripheral> {
+        self.connected_peripheral
+            .read()
+            .expect("connected peripheral lock poisoned")
+            .clone()
+    }
+
+    async fn set_connected_dev(&self, dev: Option<Peripheral>) {
+        let mut connected_dev = self.connected_dev.lock().await;
+        *self
+            .connected_peripheral
+            .write()
+            .expect("connected peripheral lock poisoned") = dev.clone();
+        *connected_dev = dev;
+    }
+
     /// Disconnects from the connected device
-    /// This triggers a disconnect and then waits for the actual disconnect event from the adapter
+    /// Pending operations get up to 2 seconds to finish before the link is torn down, see
+    /// [`Handler::flush`]. This triggers a disconnect and then waits for the actual disconnect
+    /// event from the adapter
     /// # Errors
     /// Returns an error if no device is connected or if the disconnect fails
-    /// # Panics
-    /// panics if there is an error with handling the internal disconnect event
     pub async fn disconnect(&self) -> Result<(), Error> {
         debug!("disconnect triggered by user");
         let mut connected_rx = self.connected_rx.clone();
         {
             // Scope is important to not lock device while waiting for disconnect event
-            let dev = self.connected_dev.lock().await;
+            let dev = if self.flush(DISCONNECT_FLUSH_TIMEOUT).await.is_ok() {
+                self.connected_dev.lock().await.clone()
+            } else {
+                warn!("pending operations did not finish in time, disconnecting anyway");
+                self.connected_peripheral()
+            };
             if let Some(dev) = dev.as_ref() {
                 if let Ok(true) = dev.is_connected().await {
-                    assert!(
-                        (*connected_rx.borrow_and_update()),
-                        "connected_rx is false with a device being connected, this is a bug"
-                    );
-                    dev.disconnect().await?;
+                    if connected_rx.borrow_and_update().is_none() {
+                        // the adapter reported the disconnect before the device state changed,
+                        // so no disconnect event follows and the state is cleaned up here
+                        warn!("device is connected but the connection state is not set, disconnecting anyway");
+                        self.state.lock().await.disconnect_requested = true;
+                        let res = dev.disconnect().await;
+                        self.clear_connection().await;
+                        return Ok(res?);
+                    }
+                    self.state.lock().await.disconnect_requested = true;
+                    if let Err(e) = dev.disconnect().await {
+                        self.state.lock().await.disconnect_requested = false;
+                        return Err(e.into());
+                    }
                 } else {
                     debug!("device is not connected");
                     return Err(Error::NoDeviceConnected);
@@ -337,19 +1310,153 @@ impl Handler {
         debug!("waiting for disconnect event");
         // the change will be triggered by handle_event -> handle_disconnect which runs in another
         // task
-        connected_rx
-            .changed()
-            .await
-            .expect("failed to wait for disconnect event");
-        if *self.connected_rx.borrow() {
+        if let Err(e) = connected_rx.changed().await {
+            error!("failed to wait for disconnect event: {e}");
+            return Err(Error::DisconnectFailed);
+        }
+        if self.connected_rx.borrow().is_some() {
             // still connected
             return Err(Error::DisconnectFailed);
         }
         Ok(())
     }
 
+    /// Disconnects all devices connected to the adapter, not only the one connected by this handler
+    /// This is useful to get a clean state if a previous run of the app left a connection open.
+    /// On Android this also disconnects devices the system reports as connected.
+    /// # Errors
+    /// Returns an error if the connected devices can't be queried.
+    /// Failing to disconnect a single device is only logged.
+    pub async fn disconnect_all(&self) -> Result<(), Error> {
+        // disconnect the tracked device first so the internal state is cleaned up
+        if self.is_connected() {
+            if let Err(e) = self.disconnect().await {
+                warn!("Failed to disconnect connected device: {e}");
+            }
+        }
+        #[cfg(target_os = "android")]
+        crate::android::disconnect_all()?;
+        #[cfg(not(target_os = "android"))]
+        for p in self.adapter().peripherals().await? {
+            if p.is_connected().await.unwrap_or(false) {
+                debug!("disconnecting {}", p.id());
+                if let Err(e) = p.disconnect().await {
+                    warn!("Failed to disconnect {}: {e}", p.id());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns which optional features the local Bluetooth controller supports
+    /// On Android the features are queried from the adapter. Other platforms don't expose
+    /// this information, so all fields are `None` there.
+    /// # Errors
+    /// Returns an error if calling the Android plugin fails
+    pub async fn adapter_features(&self) -> Result<AdapterFeatures, Error> {
+        #[cfg(target_os = "android")]
+        return Ok(crate::android::adapter_features()?);
+        #[cfg(not(target_os = "android"))]
+        return Ok(AdapterFeatures::default());
+    }
+
+    /// Returns whether the Bluetooth adapter is powered on, a cheap check before starting any
+    /// BLE operation
+    /// An unknown adapter state is reported as not powered
+    /// # Errors
+    /// Returns an error if the adapter state can't be queried
+    pub async fn is_powered(&self) -> Result<bool, Error> {
+        let state = self.adapter().adapter_state().await?;
+        Ok(matches!(state, CentralState::PoweredOn))
+    }
+
+    /// Asks the user to turn on Bluetooth with the Android system dialog
+    /// Returns whether the user enabled Bluetooth. Other platforms don't allow apps to power on
+    /// the adapter, so this returns [`Error::NotSupported`] there.
+    /// # Errors
+    /// Returns an error if calling the Android plugin fails or on platforms other than Android
+    pub async fn request_enable_bluetooth(&self) -> Result<bool, Error> {
+        // the plugin call blocks until the user answered the dialog
+        #[cfg(target_os = "android")]
+        return Ok(
+            tokio::task::spawn_blocking(crate::android::request_enable_bluetooth)
+                .await
+                .expect("failed to request enabling bluetooth")?,
+        );
+        #[cfg(not(target_os = "android"))]
+        return Err(Error::NotSupported(
+            "Enabling Bluetooth from the app".to_string(),
+        ));
+    }
+
+    /// Checks if connecting with the given options is supported on this platform without
+    /// connecting
+    /// Platforms other than Android always connect over LE, so requesting
+    /// [`Transport::BrEdr`](models::Transport::BrEdr) fails there. The remaining options are
+    /// supported everywhere.
+    /// # Errors
+    /// Returns [`Error::NotSupported`] describing the first unsupported option
+    pub async fn validate_connect_options(&self, options: &ConnectOptions) -> Result<(), Error> {
+        #[cfg(not(target_os = "android"))]
+        if options.transport == models::Transport::BrEdr {
+            return Err(Error::NotSupported(
+                "Connecting over Bluetooth classic (BR/EDR)".to_string(),
+            ));
+        }
+        #[cfg(target_os = "android")]
+        let _ = options;
+        Ok(())
+    }
+
+    fn adapter(&self) -> Arc<Adapter> {
+        self.adapter.read().expect("adapter lock poisoned").clone()
+    }
+
+    /// Returns a receiver that changes whenever the adapter is replaced by [`Handler::reset`]
+    pub(crate) fn adapter_changes(&self) -> watch::Receiver<u64> {
+        self.adapter_generation.subscribe()
+    }
+
+    /// Stops everything and reinitializes the Bluetooth adapter
+    /// Stops the scan, disconnects the connected device, forgets all discovered devices and
+    /// subscriptions and acquires the adapter again. This is a recovery hatch for adapters that
+    /// got stuck in a degraded state where scans and connects keep failing, without restarting
+    /// the app.
+    /// # Errors
+    /// Returns an error if no adapter can be acquired again
+    pub async fn reset(&self) -> Result<(), Error> {
+        info!("resetting handler");
+        if let Err(e) = self.stop_scan().await {
+            warn!("Failed to stop scan during reset: {e}");
+        }
+        if self.is_connected() {
+            // a stuck adapter might never send the disconnect event
+            match tokio::time::timeout(RESET_DISCONNECT_TIMEOUT, self.disconnect()).await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => warn!("Failed to disconnect during reset: {e}"),
+                Err(_) => warn!("Timed out disconnecting during reset"),
+            }
+        }
+        if self.connected_dev.lock().await.is_some() {
+            self.clear_connection().await;
+        }
+        self.devices.lock().await.clear();
+        let central = get_central().await?;
+        *self.adapter.write().expect("adapter lock poisoned") = Arc::new(central);
+        // restarts the event loop with the new adapter
+        self.adapter_generation
+            .send_modify(|generation| *generation += 1);
+        Ok(())
+    }
+
     /// Clears internal state, updates connected flag and calls disconnect callback
     async fn handle_disconnect(&self, peripheral_id: PeripheralId) -> Result<(), Error> {
+        self.send_any_disconnect(&peripheral_id).await;
+        if self.connected_rx.borrow().as_ref() == Some(&peripheral_id) {
+            // operations in flight hold the connection lock, so they are failed before locking
+            self.disconnect_generation
+                .send_modify(|generation| *generation += 1);
+        }
         let connected = self
             .connected_dev
             .lock()
@@ -361,23 +1468,56 @@ impl Handler {
             warn!("Unexpected disconnect event for device {peripheral_id}, connected device is {connected:?}",);
             return Ok(());
         }
+        self.clear_connection().await;
+        Ok(())
+    }
+
+    /// Runs an operation on the connected device, failing with
+    /// [`Error::DisconnectedDuringOperation`] as soon as the device disconnects instead of
+    /// waiting for the platform to time out
+    async fn cancel_on_disconnect<T>(
+        &self,
+        op: impl Future<Output = Result<T, btleplug::Error>>,
+    ) -> Result<T, Error> {
+        let mut disconnects = self.disconnect_generation.subscribe();
+        let disconnected = pin!(disconnects.changed());
+        match select(pin!(op), disconnected).await {
+            Either::Left((res, _)) => res.map_err(Error::from),
+            Either::Right(_) => Err(Error::DisconnectedDuringOperation),
+        }
+    }
+
+    async fn clear_connection(&self) {
+        let reason;
         {
             debug!("locking state for disconnect");
             let mut state = self.state.lock().await;
             info!("disconnecting");
-            *self.connected_dev.lock().await = None;
+            self.set_connected_dev(None).await;
             if let Some(handle) = state.listen_handle.take() {
                 handle.abort();
             }
+            if let Some(handle) = state.keepalive_task.take() {
+                handle.abort();
+            }
             *self.notify_listeners.lock().await = vec![];
             state.on_disconnect.take().run().await;
             state.characs.clear();
+            state.services.clear();
+            state.mtu = None;
+            reason = if std::mem::take(&mut state.disconnect_requested) {
+                DisconnectReason::Requested
+            } else {
+                DisconnectReason::ConnectionLost
+            };
         }
         self.send_connection_update(false).await;
+        self.set_connection_status(ConnectionStatus::Disconnected)
+            .await;
+        self.send_status_event(BleStatusEvent::Disconnected(reason));
         self.connected_tx
-            .send(false)
+            .send(None)
             .expect("failed to send connected update");
-        Ok(())
     }
 
     /// Scans for `timeout` milliseconds and periodically sends discovered devices
@@ -387,6 +1527,13 @@ impl Handler {
     ///
     /// A Variant of [`ScanFilter`] can be provided to filter the discovered devices
     ///
+    /// The returned [`ScanHandle`] stops exactly this scan, dropping it keeps the scan running
+    ///
+    /// Starting a scan while another one is running replaces the running scan. The update
+    /// channel of the replaced scan is closed, so its receiver ends, and
+    /// [`BleStatusEvent::ScanSuperseded`] is emitted. Set [`ScanOptions::fail_if_scanning`] to
+    /// get [`Error::ScanAlreadyRunning`] instead.
+    ///
     /// # Errors
     /// Returns an error if starting the scan fails
     /// # Panics
@@ -411,7 +1558,127 @@ impl Handler {
         tx: Option<mpsc::Sender<Vec<BleDevice>>>,
         timeout: u64,
         filter: ScanFilter,
-    ) -> Result<(), Error> {
+    ) -> Result<ScanHandle, Error> {
+        self.start_scan(tx, None, timeout, filter, ScanOptions::default(), None)
+            .await
+    }
+
+    /// Same as [`Handler::discover`] but allows to configure how devices are reported
+    /// with [`ScanOptions`]
+    ///
+    /// # Errors
+    /// Returns an error if starting the scan fails
+    /// # Panics
+    /// Panics if there is an error getting devices from the adapter
+    /// # Example
+    /// ```no_run
+    /// use tauri::async_runtime;
+    /// use tokio::sync::mpsc;
+    /// use tauri_plugin_blec::models::{ScanFilter, ScanOptions};
+    ///
+    /// async_runtime::block_on(async {
+    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///     let (tx, mut rx) = mpsc::channel(1);
+    ///     let options = ScanOptions {
+    ///         update_interval: Some(1000),
+    ///         only_changed: true,
+    ///         ..Default::default()
+    ///     };
+    ///     handler.discover_with_options(Some(tx), 10000, ScanFilter::None, options).await.unwrap();
+    ///     while let Some(devices) = rx.recv().await {
+    ///         println!("New or changed {devices:?}");
+    ///     }
+    /// });
+    /// ```
+    pub async fn discover_with_options(
+        &'static self,
+        tx: Option<mpsc::Sender<Vec<BleDevice>>>,
+        timeout: u64,
+        filter: ScanFilter,
+        options: ScanOptions,
+    ) -> Result<ScanHandle, Error> {
+        self.start_scan(tx, None, timeout, filter, options, None)
+            .await
+    }
+
+    /// Same as [`Handler::discover_with_options`] but sends every newly discovered device on
+    /// its own as soon as it is found instead of the list of all devices
+    /// Each device is sent once per scan, so a picker can show the first device without
+    /// waiting for the next update of the full list. Devices are sent right when the adapter
+    /// reports them, before names are resolved or services discovered, so these fields may
+    /// still be missing. Android doesn't report discovery events, there devices are sent with
+    /// the next poll of the adapter. With [`ScanOptions::require_characteristic`] devices are
+    /// only sent after the check.
+    /// # Errors
+    /// Returns an error if starting the scan fails
+    /// # Panics
+    /// Panics if there is an error getting devices from the adapter
+    pub async fn discover_per_device(
+        &'static self,
+        tx: mpsc::Sender<BleDevice>,
+        timeout: u64,
+        filter: ScanFilter,
+        options: ScanOptions,
+    ) -> Result<ScanHandle, Error> {
+        self.start_scan(None, Some(tx), timeout, filter, options, None)
+            .await
+    }
+
+    /// Scans for up to `timeout` milliseconds and returns the first device for which
+    /// `predicate` returns true.
+    /// The scan is stopped as soon as a matching device is found, so this can be used to
+    /// connect to a device without waiting for the whole timeout.
+    /// Returns `None` if no matching device was found or the scan was stopped early
+    ///
+    /// # Errors
+    /// Returns an error if starting the scan fails
+    /// # Example
+    /// ```no_run
+    /// use tauri::async_runtime;
+    /// use tauri_plugin_blec::models::ScanFilter;
+    ///
+    /// async_runtime::block_on(async {
+    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///     let device = handler
+    ///         .scan_until(10000, ScanFilter::None, |d| d.name == "gatt_server")
+    ///         .await
+    ///         .unwrap();
+    ///     if let Some(device) = device {
+    ///         handler.connect(&device.address, (|| println!("disconnected")).into()).await.unwrap();
+    ///     }
+    /// });
+    /// ```
+    pub async fn scan_until(
+        &'static self,
+        timeout: u64,
+        filter: ScanFilter,
+        predicate: impl Fn(&BleDevice) -> bool + Send + 'static,
+    ) -> Result<Option<BleDevice>, Error> {
+        let (found_tx, found_rx) = oneshot::channel();
+        self.start_scan(
+            None,
+            None,
+            timeout,
+            filter,
+            ScanOptions::default(),
+            Some(StopCondition {
+                predicate: Box::new(predicate),
+                found: found_tx,
+            }),
+        )
+        .await?;
+        Ok(found_rx.await.ok())
+    }
+
+    async fn start_scan(
+        &'static self,
+        tx: Option<mpsc::Sender<Vec<BleDevice>>>,
+        device_tx: Option<mpsc::Sender<BleDevice>>,
+        timeout: u64,
+        filter: ScanFilter,
+        options: ScanOptions,
+        mut stop_on: Option<StopCondition>,
+    ) -> Result<ScanHandle, Error> {
         if let ScanFilter::ManufacturerDataMasked(_, ref data, ref mask) = filter {
             if data.len() != mask.len() {
                 return Err(Error::InvalidFilterMask);
@@ -419,44 +1686,227 @@ impl Handler {
         }
         {
             let mut state = self.state.lock().await;
-            // stop any ongoing scan
+            let running = matches!(&state.scan_task, Some(handle) if !handle.is_finished());
+            if running && options.fail_if_scanning {
+                return Err(Error::ScanAlreadyRunning);
+            }
+            // stop any ongoing scan, this drops its update channel
             if let Some(handle) = state.scan_task.take() {
                 handle.abort();
-                self.adapter.stop_scan().await?;
+                self.adapter().stop_scan().await?;
+                if running {
+                    debug!("running scan superseded by a new scan");
+                    self.send_status_event(BleStatusEvent::ScanSuperseded);
+                }
             }
             // start a new scan
-            self.adapter
+            #[cfg(target_os = "android")]
+            crate::android::set_scan_settings(options.scan_mode, options.extended_advertising);
+            self.adapter()
                 .start_scan(btleplug::api::ScanFilter::default())
                 .await?;
         }
+        let generation = self.scan_generation.fetch_add(1, Ordering::AcqRel) + 1;
+        self.scanning.store(generation, Ordering::Release);
         self.send_scan_update(true).await;
         let mut state = self.state.lock().await;
+        state.scan_id += 1;
+        let handle = ScanHandle {
+            handler: self,
+            id: state.scan_id,
+        };
         let mut self_devices = self.devices.clone();
-        let adapter = self.adapter.clone();
+        let device_key = state.device_key;
+        let adapter = self.adapter().clone();
         state.scan_task = Some(tokio::task::spawn(async move {
+            let _scanning = ScanningGuard {
+                handler: self,
+                generation,
+            };
             self_devices.lock().await.clear();
             let loops = timeout / 200;
-            let mut devices;
+            let mut devices = vec![];
+            let mut found = None;
+            let mut seen = HashSet::new();
+            let coalesce = options.update_interval.is_some();
+            let discover_services = options.discover_services;
+            let max_devices = options.max_devices;
+            let sort_by = options.sort_by;
+            // resolved names by peripheral, `None` if resolving failed
+            let mut names = options.resolve_names.then(HashMap::new);
+            let service_filter = matches!(
+                filter,
+                ScanFilter::Service(_) | ScanFilter::AnyService(_) | ScanFilter::AllServices(_)
+            );
+            // result of the characteristic check by peripheral
+            let mut checked = HashMap::new();
+            let require_characteristic = options.require_characteristic.filter(|_| service_filter);
+            // discovered GATT services by address, `None` if discovering failed
+            let mut gatt_services =
+                (service_filter && options.discover_filtered_services).then(HashMap::new);
+            let mut updates = ScanUpdates::new(options);
+            // addresses sent to the per-device channel
+            let mut sent = HashSet::new();
+            // devices failing the characteristic check must not be sent before it ran
+            let mut discoveries = device_tx
+                .as_ref()
+                .filter(|_| require_characteristic.is_none())
+                .map(|_| self.discovered_tx.subscribe());
             for _ in 0..loops {
-                sleep(Duration::from_millis(200)).await;
+                let next_poll = tokio::time::Instant::now() + Duration::from_millis(200);
+                if let (Some(discoveries), Some(device_tx)) = (discoveries.as_mut(), &device_tx) {
+                    // send devices reported by the adapter right away instead of on the next poll
+                    while let Ok(discovery) =
+                        tokio::time::timeout_at(next_poll, discoveries.recv()).await
+                    {
+                        match discovery {
+                            Ok(id) => {
+                                self.forward_discovered(
+                                    &adapter,
+                                    &id,
+                                    &filter,
+                                    device_key,
+                                    &self_devices,
+                                    &mut sent,
+                                    device_tx,
+                                )
+                                .await;
+                            }
+                            Err(broadcast::error::RecvError::Lagged(_)) => {}
+                            Err(broadcast::error::RecvError::Closed) => break,
+                        }
+                    }
+                }
+                tokio::time::sleep_until(next_poll).await;
                 let mut discovered = adapter
                     .peripherals()
                     .await
                     .expect("failed to get peripherals");
                 filter_peripherals(&mut discovered, &filter).await;
-                devices = Self::add_devices(&mut self_devices, discovered).await;
-                if !devices.is_empty() {
-                    if let Some(tx) = &tx {
-                        tx.send(devices.clone())
-                            .await
-                            .expect("failed to send devices");
+                if let Some(charac) = require_characteristic {
+                    let connected = self.connected_rx.borrow().clone();
+                    let unchecked: Vec<_> = discovered
+                        .iter()
+                        .filter(|p| !checked.contains_key(&p.id()))
+                        .collect();
+                    let checks: Vec<_> = unchecked
+                        .iter()
+                        .map(|p| has_characteristic(p, charac, connected.as_ref()))
+                        .collect();
+                    let found: Vec<_> = futures::stream::iter(checks)
+                        .buffered(MAX_TEMPORARY_CONNECTIONS)
+                        .collect()
+                        .await;
+                    for (p, found) in unchecked.iter().zip(found) {
+                        checked.insert(p.id(), found);
+                    }
+                    discovered.retain(|p| checked.get(&p.id()) == Some(&true));
+                }
+                devices = Self::add_devices(
+                    &mut self_devices,
+                    &discovered,
+                    discover_services,
+                    device_key,
+                )
+                .await;
+                self.apply_tags(&mut devices).await;
+                // send new devices before the slow steps below
+                if let Some(device_tx) = &device_tx {
+                    for dev in &devices {
+                        if sent.insert(dev.address.clone()) {
+                            // a closed receiver only means nobody is interested anymore
+                            let _ = device_tx.send(dev.clone()).await;
+                        }
+                    }
+                }
+                if let Some(names) = names.as_mut() {
+                    let connected = self.connected_rx.borrow().clone();
+                    resolve_names(&discovered, names, connected.as_ref()).await;
+                    let known = self_devices.lock().await;
+                    for dev in &mut devices {
+                        let Some(p) = known.get(&dev.address) else {
+                            continue;
+                        };
+                        // devices without a local name get their id as name
+                        if dev.name == p.id().to_string() {
+                            if let Some(Some(name)) = names.get(&p.id()) {
+                                dev.name.clone_from(name);
+                            }
+                        }
+                    }
+                }
+                if let Some(max) = max_devices {
+                    Self::limit_devices(&self_devices, &mut devices, max).await;
+                }
+                if let Some(gatt_services) = gatt_services.as_mut() {
+                    let pending: Vec<(String, Peripheral)> = {
+                        let known = self_devices.lock().await;
+                        devices
+                            .iter()
+                            .filter(|d| !gatt_services.contains_key(&d.address))
+                            .filter_map(|d| {
+                                Some((d.address.clone(), known.get(&d.address)?.clone()))
+                            })
+                            .collect()
+                    };
+                    let connected = self.connected_rx.borrow().clone();
+                    let discoveries: Vec<_> = pending
+                        .iter()
+                        .map(|(_, p)| discover_gatt_services(p, connected.as_ref()))
+                        .collect();
+                    let results: Vec<_> = futures::stream::iter(discoveries)
+                        .buffered(MAX_TEMPORARY_CONNECTIONS)
+                        .collect()
+                        .await;
+                    for ((address, _), services) in pending.into_iter().zip(results) {
+                        gatt_services.insert(address, services);
+                    }
+                    for dev in &mut devices {
+                        dev.gatt_services = gatt_services.get(&dev.address).cloned().flatten();
+                    }
+                }
+                // rssi changes over time, so the order is updated on every poll
+                sort_by.sort(&mut devices);
+                for dev in &devices {
+                    if seen.insert(dev.address.clone()) {
+                        self.send_status_event(BleStatusEvent::DeviceDiscovered(dev.clone()));
+                    }
+                }
+                if let Some(tx) = &tx {
+                    if let Some(update) = updates.next(&devices, false) {
+                        if !update.is_empty() {
+                            tx.send(update).await.expect("failed to send devices");
+                        }
+                    }
+                }
+                // exit right away on a match instead of finishing the remaining intervals
+                if let Some(stop) = &stop_on {
+                    found = devices.iter().find(|d| (stop.predicate)(d)).cloned();
+                    if found.is_some() {
+                        break;
+                    }
+                }
+            }
+            // send changes that were held back by the update interval
+            if let (Some(tx), true) = (&tx, coalesce) {
+                if let Some(update) = updates.next(&devices, true) {
+                    if !update.is_empty() {
+                        tx.send(update).await.expect("failed to send devices");
                     }
                 }
             }
-            adapter.stop_scan().await.expect("failed to stop scan");
+            if self.state.lock().await.service_watches.is_empty() {
+                adapter.stop_scan().await.expect("failed to stop scan");
+            }
             self.send_scan_update(false).await;
+            if let (Some(stop), Some(device)) = (stop_on.take(), found.clone()) {
+                let _ = stop.found.send(device);
+            }
+            if found.is_none() {
+                self.send_scan_complete(seen.len()).await;
+            }
         }));
-        Ok(())
+        Ok(handle)
     }
 
     /// Discover provided services and charecteristics
@@ -464,16 +1914,18 @@ impl Handler {
     /// After the discovery is done, the device is disconnected
     /// If the devices was already connected, it will stay connected
     /// # Errors
-    /// Returns an error if the device is not found, if the connection fails, or if the discovery fails
+    /// Returns an error if the device is not found, if the connection fails, or if the discovery fails.
+    /// Returns [`Error::Timeout`] if the discovery takes longer than `timeout`
     /// # Panics
     /// Panics if there is an error with the internal disconnect event
-    pub async fn discover_services(&self, address: &str) -> Result<Vec<Service>, Error> {
-        let mut already_connected = self
-            .connected_dev
-            .lock()
-            .await
-            .as_ref()
-            .is_some_and(|dev| address == fmt_addr(dev.address()));
+    pub async fn discover_services(
+        &self,
+        address: &str,
+        timeout: Duration,
+    ) -> Result<Vec<Service>, Error> {
+        let connected_id = self.connected_dev.lock().await.as_ref().map(|dev| dev.id());
+        let mut already_connected = connected_id.is_some()
+            && self.devices.lock().await.get(address).map(|p| p.id()) == connected_id;
         let device = if already_connected {
             self.connected_dev
                 .lock()
@@ -491,22 +1943,33 @@ impl Handler {
                 .clone();
             if device.is_connected().await? {
                 already_connected = true;
-            } else if let Err(e) = self.connect_device(address).await {
-                *self.connected_dev.lock().await = None;
-                let _ = self.connected_tx.send(false);
+            } else if let Err(e) = self
+                .connect_device(address, &ConnectOptions::default())
+                .await
+            {
+                self.set_connected_dev(None).await;
+                let _ = self.connected_tx.send(None);
                 error!("Failed to connect for discovery: {e}");
                 return Err(e);
             }
             device
         };
         debug!("discovering services on {address}");
-        if device.services().is_empty() {
-            device.discover_services().await?;
-        }
-        let services = device.services().iter().map(Service::from).collect();
+        let discovered = if device.services().is_empty() {
+            match tokio::time::timeout(timeout, device.discover_services()).await {
+                Ok(res) => res.map_err(Error::from),
+                Err(_) => {
+                    warn!("Timed out discovering services on {address}");
+                    Err(Error::Timeout)
+                }
+            }
+        } else {
+            Ok(())
+        };
+        let services = discovered.map(|()| device.services().iter().map(Service::from).collect());
         if !already_connected {
             let mut connected_rx = self.connected_rx.clone();
-            if *connected_rx.borrow_and_update() {
+            if connected_rx.borrow_and_update().is_some() {
                 device.disconnect().await?;
                 debug!("waiting for disconnect event");
                 connected_rx
@@ -515,45 +1978,343 @@ impl Handler {
                     .expect("failed to wait for disconnect event");
             }
         }
-        Ok(services)
-    }
-
-    /// Stops scanning for devices
-    /// # Errors
-    /// Returns an error if stopping the scan fails
-    pub async fn stop_scan(&self) -> Result<(), Error> {
-        self.adapter.stop_scan().await?;
-        if let Some(handle) = self.state.lock().await.scan_task.take() {
-            handle.abort();
-        }
-        self.send_scan_update(false).await;
-        Ok(())
-    }
-
-    async fn add_devices(
-        self_devices: &mut Arc<Mutex<HashMap<String, Peripheral>>>,
-        discovered: Vec<Peripheral>,
-    ) -> Vec<BleDevice> {
-        let mut devices = vec![];
-        for p in discovered {
-            match BleDevice::from_peripheral(&p).await {
-                Ok(dev) => {
-                    self_devices.lock().await.insert(dev.address.clone(), p);
-                    devices.push(dev);
-                }
-                Err(e) => {
-                    warn!("Failed to add device: {e}");
-                }
-            }
-        }
-        devices.sort();
-        devices
+        services
     }
 
-    /// Sends data to the given characteristic of the connected device
+    /// Watches for devices advertising the given service and sends a device to `tx` whenever it
+    /// starts advertising the service
+    /// A device that stops advertising the service is reported again once it advertises it
+    /// again, e.g. after rebooting into a bootloader. The adapter keeps scanning until the
+    /// returned id is passed to [`Handler::unwatch_for_service`] or `tx` is closed, scans started
+    /// in the meantime don't stop it.
     /// # Errors
-    /// Returns an error if no device is connected or the characteristic is not available
-    /// or if the write operation fails
+    /// Returns an error if starting the scan fails
+    /// # Example
+    /// ```no_run
+    /// use tauri::async_runtime;
+    /// use tokio::sync::mpsc;
+    /// use uuid::{Uuid,uuid};
+    /// const DFU_SERVICE: Uuid = uuid!("0000FE59-0000-1000-8000-00805F9B34FB");
+    ///
+    /// async_runtime::block_on(async {
+    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///     let (tx, mut rx) = mpsc::channel(1);
+    ///     let watch = handler.watch_for_service(DFU_SERVICE, tx).await.unwrap();
+    ///     let device = rx.recv().await.unwrap();
+    ///     println!("{} is in bootloader mode", device.address);
+    ///     handler.unwatch_for_service(watch).await;
+    /// });
+    /// ```
+    pub async fn watch_for_service(
+        &'static self,
+        service: Uuid,
+        tx: mpsc::Sender<BleDevice>,
+    ) -> Result<u64, Error> {
+        self.adapter()
+            .start_scan(btleplug::api::ScanFilter::default())
+            .await?;
+        let id = {
+            let mut state = self.state.lock().await;
+            state.watch_id += 1;
+            let id = state.watch_id;
+            state.service_watches.insert(id);
+            id
+        };
+        let filter = ScanFilter::Service(service);
+        async_runtime::spawn(async move {
+            // addresses currently advertising the service
+            let mut advertising = HashSet::new();
+            while !tx.is_closed() && self.state.lock().await.service_watches.contains(&id) {
+                sleep(Duration::from_millis(200)).await;
+                let mut discovered = match self.adapter().peripherals().await {
+                    Ok(discovered) => discovered,
+                    Err(e) => {
+                        warn!("Failed to get peripherals while watching for {service}: {e}");
+                        continue;
+                    }
+                };
+                filter_peripherals(&mut discovered, &filter).await;
+                let mut current = HashSet::new();
+                let key = self.device_key().await;
+                for p in discovered {
+                    let mut device = match BleDevice::from_peripheral(&p, key).await {
+                        Ok(device) => device,
+                        Err(e) => {
+                            warn!("Failed to add device: {e}");
+                            continue;
+                        }
+                    };
+                    if let Some(known) = known_key(&*self.devices.lock().await, &p) {
+                        device.address = known;
+                    }
+                    current.insert(device.address.clone());
+                    if !advertising.contains(&device.address) {
+                        debug!("{} started advertising {service}", device.address);
+                        self.devices.lock().await.insert(device.address.clone(), p);
+                        if tx.send(device).await.is_err() {
+                            break;
+                        }
+                    }
+                }
+                advertising = current;
+            }
+            let mut state = self.state.lock().await;
+            state.service_watches.remove(&id);
+            let scanning = matches!(&state.scan_task, Some(handle) if !handle.is_finished());
+            if state.service_watches.is_empty() && !scanning {
+                if let Err(e) = self.adapter().stop_scan().await {
+                    warn!("Failed to stop scan after watching for {service}: {e}");
+                }
+            }
+        });
+        Ok(id)
+    }
+
+    /// Stops a watch started with [`Handler::watch_for_service`]
+    /// The adapter stops scanning once no watch and no scan is running anymore. Does nothing
+    /// if the watch already ended
+    pub async fn unwatch_for_service(&self, id: u64) {
+        self.state.lock().await.service_watches.remove(&id);
+    }
+
+    /// Stops scanning for devices
+    /// # Errors
+    /// Returns an error if stopping the scan fails
+    pub async fn stop_scan(&self) -> Result<(), Error> {
+        let mut state = self.state.lock().await;
+        if state.service_watches.is_empty() {
+            self.adapter().stop_scan().await?;
+        }
+        if let Some(handle) = state.scan_task.take() {
+            handle.abort();
+        }
+        drop(state);
+        self.send_scan_update(false).await;
+        Ok(())
+    }
+
+    async fn add_devices(
+        self_devices: &mut Arc<Mutex<HashMap<String, Peripheral>>>,
+        discovered: &[Peripheral],
+        discover_services: bool,
+        key: DeviceKey,
+    ) -> Vec<BleDevice> {
+        let mut devices = vec![];
+        for p in discovered {
+            match BleDevice::from_peripheral(p, key).await {
+                Ok(mut dev) => {
+                    if let Some(known) = known_key(&*self_devices.lock().await, p) {
+                        dev.address = known;
+                    }
+                    if discover_services && dev.services.is_empty() {
+                        // services stay cached in the peripheral, so this only runs once per device.
+                        // Discovering needs a connection, which a scan must not open implicitly
+                        if p.services().is_empty() && p.is_connected().await.unwrap_or(false) {
+                            if let Err(e) = p.discover_services().await {
+                                warn!("Failed to discover services of {}: {e}", dev.address);
+                            }
+                        }
+                        dev.services = p.services().iter().map(|s| s.uuid).collect();
+                    }
+                    self_devices
+                        .lock()
+                        .await
+                        .insert(dev.address.clone(), p.clone());
+                    devices.push(dev);
+                }
+                Err(e) => {
+                    warn!("Failed to add device: {e}");
+                }
+            }
+        }
+        devices.sort();
+        devices
+    }
+
+    pub(crate) fn set_data_dir(&self, dir: PathBuf) {
+        let _ = self.data_dir.set(dir);
+    }
+
+    fn remembered_devices_path(&self) -> Result<PathBuf, Error> {
+        self.data_dir
+            .get()
+            .map(|dir| dir.join(REMEMBERED_DEVICES_FILE))
+            .ok_or(Error::StorageNotAvailable)
+    }
+
+    /// Stores a discovered device in the app data dir, so it can be connected directly on the
+    /// next app launch. Name and advertised services are stored along with the address.
+    /// If a tag is given, it is also used as the tag of the device, see [`ConnectOptions::tag`]
+    /// Nothing is stored unless this method is called
+    /// # Errors
+    /// Returns an error if the device is unknown or writing the file fails
+    pub async fn remember_device(
+        &self,
+        address: &str,
+        tag: Option<String>,
+    ) -> Result<RememberedDevice, Error> {
+        let key = self.device_key().await;
+        let p = self
+            .devices
+            .lock()
+            .await
+            .get(address)
+            .ok_or(Error::UnknownPeripheral(address.to_string()))?
+            .clone();
+        let mut device = BleDevice::from_peripheral(&p, key).await?;
+        device.address = address.to_string();
+        if let Some(tag) = &tag {
+            self.state
+                .lock()
+                .await
+                .tags
+                .insert(device.address.clone(), tag.clone());
+        }
+        let remembered = RememberedDevice {
+            address: device.address,
+            name: device.name,
+            services: device.services,
+            tag,
+        };
+        let mut devices = self.remembered_devices()?;
+        devices.retain(|d| d.address != remembered.address);
+        devices.push(remembered.clone());
+        self.store_remembered_devices(&devices)?;
+        Ok(remembered)
+    }
+
+    /// Returns the devices stored with [`Handler::remember_device`]
+    /// # Errors
+    /// Returns an error if the file exists but can't be read
+    pub fn remembered_devices(&self) -> Result<Vec<RememberedDevice>, Error> {
+        match std::fs::read(self.remembered_devices_path()?) {
+            Ok(data) => Ok(serde_json::from_slice(&data)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(vec![]),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Removes a device stored with [`Handler::remember_device`]
+    /// # Errors
+    /// Returns an error if reading or writing the file fails
+    pub fn forget_device(&self, address: &str) -> Result<(), Error> {
+        let mut devices = self.remembered_devices()?;
+        devices.retain(|d| d.address != address);
+        self.store_remembered_devices(&devices)
+    }
+
+    fn store_remembered_devices(&self, devices: &[RememberedDevice]) -> Result<(), Error> {
+        let path = self.remembered_devices_path()?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_vec_pretty(devices)?)?;
+        Ok(())
+    }
+
+    /// Sets the write type used by the frontend commands when no write type is given
+    /// Defaults to [`WriteType::WithResponse`](models::WriteType::WithResponse)
+    pub async fn set_default_write_type(&self, write_type: models::WriteType) {
+        self.state.lock().await.default_write_type = write_type;
+    }
+
+    /// Returns the write type used by the frontend commands when no write type is given
+    pub async fn default_write_type(&self) -> models::WriteType {
+        self.state.lock().await.default_write_type
+    }
+
+    /// Sets the key that identifies discovered devices, see [`DeviceKey`]
+    /// The key is reported as [`BleDevice::address`] and is what [`Handler::connect`] expects.
+    /// Defaults to [`DeviceKey::Address`]. Set it once during setup, changing it forgets all
+    /// discovered devices because their keys change. A scan that is already running keeps
+    /// using the previous key.
+    /// A device keeps the key it was first seen with until the next scan. Tags and remembered
+    /// devices are stored by key and are not migrated, they don't match devices found with
+    /// another key.
+    pub async fn set_device_key(&self, key: DeviceKey) {
+        let changed = {
+            let mut state = self.state.lock().await;
+            std::mem::replace(&mut state.device_key, key) != key
+        };
+        if changed {
+            self.devices.lock().await.clear();
+        }
+    }
+
+    /// Returns the key that identifies discovered devices, see [`Handler::set_device_key`]
+    pub async fn device_key(&self) -> DeviceKey {
+        self.state.lock().await.device_key
+    }
+
+    /// Sets how often enabling notifications is retried after a transient failure, waiting
+    /// `delay` before each retry
+    /// On Android the CCCD write sometimes fails with GATT 133 right after connecting and
+    /// succeeds when tried again. Applies to all subscribe methods, defaults to no retries
+    pub async fn set_subscribe_retries(&self, retries: u32, delay: Duration) {
+        self.state.lock().await.subscribe_retries = (retries, delay);
+    }
+
+    /// Restricts the characteristics the frontend commands are allowed to write to
+    /// Writes from the frontend to any other characteristic fail with
+    /// [`Error::WriteNotPermitted`], as do writes by handle and to descriptors of other
+    /// characteristics. Writes made through the handler from Rust are not restricted.
+    pub async fn set_writable_allowlist(&self, allowlist: Vec<Uuid>) {
+        self.state.lock().await.writable_allowlist = Some(allowlist.into_iter().collect());
+    }
+
+    /// Removes the allowlist set with [`Handler::set_writable_allowlist`], so the frontend can
+    /// write to all characteristics again
+    pub async fn clear_writable_allowlist(&self) {
+        self.state.lock().await.writable_allowlist = None;
+    }
+
+    /// Checks if the frontend is allowed to write to the given characteristic
+    pub(crate) async fn check_writable(&self, c: Uuid) -> Result<(), Error> {
+        match &self.state.lock().await.writable_allowlist {
+            Some(allowlist) if !allowlist.contains(&c) => {
+                Err(Error::WriteNotPermitted(c.to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks if the frontend is allowed to write by handle, which is only the case without an
+    /// allowlist because the characteristic behind a handle is not known
+    pub(crate) async fn check_writable_handle(&self, handle: u16) -> Result<(), Error> {
+        if self.state.lock().await.writable_allowlist.is_some() {
+            return Err(Error::WriteNotPermitted(format!("handle {handle}")));
+        }
+        Ok(())
+    }
+
+    /// Keeps only the `max` devices with the strongest signal
+    async fn limit_devices(
+        self_devices: &Arc<Mutex<HashMap<String, Peripheral>>>,
+        devices: &mut Vec<BleDevice>,
+        max: usize,
+    ) {
+        if devices.len() <= max {
+            return;
+        }
+        // devices without rssi are sorted last
+        devices.sort_by_key(|d| std::cmp::Reverse(d.rssi));
+        let evicted = devices.split_off(max);
+        let mut self_devices = self_devices.lock().await;
+        for d in evicted {
+            self_devices.remove(&d.address);
+        }
+        devices.sort();
+    }
+
+    /// Sends data to the given characteristic of the connected device
+    /// Use [`Handler::supported_write_types`] to check which `write_type` the characteristic
+    /// accepts
+    /// # Errors
+    /// Returns an error if no device is connected or the characteristic is not available,
+    /// [`Error::NotWritable`] if the characteristic supports no writes,
+    /// [`Error::WriteTypeNotSupported`] if it does not support `write_type`,
+    /// [`Error::DisconnectedDuringOperation`] if the device disconnects during the write
+    /// or if the write operation fails
     /// # Example
     /// ```no_run
     /// use tauri::async_runtime;
@@ -573,84 +2334,1358 @@ impl Handler {
         data: &[u8],
         write_type: models::WriteType,
     ) -> Result<(), Error> {
+        self.write_charac(None, c, data, write_type).await
+    }
+
+    /// Sends data to the given characteristic of the given service
+    /// Use this if the device has the same characteristic UUID in several services, e.g. in
+    /// a primary and an included service. Otherwise this behaves like [`Handler::send_data`]
+    /// # Errors
+    /// Returns an error if no device is connected or the service has no such characteristic,
+    /// [`Error::NotWritable`] if the characteristic supports no writes,
+    /// [`Error::WriteTypeNotSupported`] if it does not support `write_type`
+    /// or if the write operation fails
+    pub async fn send_data_in_service(
+        &self,
+        service: Uuid,
+        c: Uuid,
+        data: &[u8],
+        write_type: models::WriteType,
+    ) -> Result<(), Error> {
+        self.write_charac(Some(service), c, data, write_type).await
+    }
+
+    async fn write_charac(
+        &self,
+        service: Option<Uuid>,
+        c: Uuid,
+        data: &[u8],
+        write_type: models::WriteType,
+    ) -> Result<(), Error> {
+        let dev = self.connected_dev.lock().await;
+        let dev = dev.as_ref().ok_or(Error::NoDeviceConnected)?;
+        let state = self.state.lock().await;
+        let charac = state.find_charac(service, c)?;
+        check_write_type(charac, write_type)?;
+        self.cancel_on_disconnect(dev.write(charac, data, write_type.into()))
+            .await?;
+        Ok(())
+    }
+
+    /// Sends data to the characteristic with the given 16-bit UUID assigned by the Bluetooth SIG
+    /// The short UUID is expanded with the Bluetooth base UUID, see [`Handler::send_data`]
+    /// # Errors
+    /// Returns an error if no device is connected or the characteristic is not available,
+    /// [`Error::NotWritable`] if the characteristic supports no writes
+    /// or if the write operation fails
+    pub async fn send_data_short(
+        &self,
+        c: u16,
+        data: &[u8],
+        write_type: models::WriteType,
+    ) -> Result<(), Error> {
+        self.send_data(uuid_from_u16(c), data, write_type).await
+    }
+
+    /// Returns the write types the given characteristic of the connected device supports
+    /// The list is empty if the characteristic is not writable
+    /// # Errors
+    /// Returns an error if no device is connected or the characteristic is not available
+    pub async fn supported_write_types(&self, c: Uuid) -> Result<Vec<models::WriteType>, Error> {
+        let dev = self.connected_dev.lock().await;
+        dev.as_ref().ok_or(Error::NoDeviceConnected)?;
+        let state = self.state.lock().await;
+        Ok(models::WriteType::supported(
+            state.get_charac(c)?.properties,
+        ))
+    }
+
+    /// Writes to a characteristic identified by its ATT value handle instead of the UUID.
+    /// This is useful if only the handle is known, e.g. from a sniffer log.
+    /// Only supported on Android
+    /// # Errors
+    /// Returns an error if no device is connected, there is no characteristic with the given
+    /// handle or the write operation fails. Returns [`Error::NotSupported`] on other platforms
+    pub async fn write_by_handle(
+        &self,
+        handle: u16,
+        data: &[u8],
+        write_type: models::WriteType,
+    ) -> Result<(), Error> {
+        let dev = self.connected_dev.lock().await;
+        let dev = dev.as_ref().ok_or(Error::NoDeviceConnected)?;
+        #[cfg(target_os = "android")]
+        {
+            use crate::android::HandleExt;
+            dev.write_by_handle(handle, data, write_type.into()).await?;
+            Ok(())
+        }
+        #[cfg(not(target_os = "android"))]
+        {
+            let _ = (dev, handle, data, write_type);
+            Err(Error::NotSupported("write_by_handle".to_string()))
+        }
+    }
+
+    /// Reads a characteristic identified by its ATT value handle instead of the UUID.
+    /// Only supported on Android
+    /// # Errors
+    /// Returns an error if no device is connected, there is no characteristic with the given
+    /// handle or the read operation fails. Returns [`Error::NotSupported`] on other platforms
+    pub async fn recv_by_handle(&self, handle: u16) -> Result<Vec<u8>, Error> {
+        let dev = self.connected_dev.lock().await;
+        let dev = dev.as_ref().ok_or(Error::NoDeviceConnected)?;
+        #[cfg(target_os = "android")]
+        {
+            use crate::android::HandleExt;
+            Ok(dev.read_by_handle(handle).await?)
+        }
+        #[cfg(not(target_os = "android"))]
+        {
+            let _ = (dev, handle);
+            Err(Error::NotSupported("recv_by_handle".to_string()))
+        }
+    }
+
+    /// Returns the MTU negotiated with the connected device, if known
+    /// The MTU is only known after a successful [`Handler::request_mtu`] on Android
+    pub async fn mtu(&self) -> Option<u16> {
+        self.state.lock().await.mtu
+    }
+
+    /// Returns the largest payload a single write can carry
+    /// This is the MTU minus the 3 byte ATT header, using the default MTU of 23 bytes if none
+    /// was negotiated. Both write types have the same limit: some platforms split longer writes
+    /// with response into prepared writes, but not every device supports them, so they are not
+    /// taken into account.
+    pub async fn max_write_length(&self) -> u16 {
+        self.state.lock().await.max_payload()
+    }
+
+    /// Writes data that may be larger than the MTU by splitting it into chunks
+    /// The chunk size is derived from the negotiated MTU, or from the default MTU of 23 bytes if
+    /// none was negotiated. The device stays locked for the whole transfer, so no other
+    /// operation can run in between. The [`ChunkWritePolicy`] selects the write type of each chunk.
+    /// # Errors
+    /// Returns an error if no device is connected or the characteristic is not available
+    /// or if writing any chunk fails
+    /// # Example
+    /// ```no_run
+    /// use tauri::async_runtime;
+    /// use uuid::{Uuid,uuid};
+    /// use tauri_plugin_blec::models::ChunkWritePolicy;
+    ///
+    /// const CHARACTERISTIC_UUID: Uuid = uuid!("51FF12BB-3ED8-46E5-B4F9-D64E2FEC021B");
+    /// async_runtime::block_on(async {
+    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///     let firmware = vec![0; 4096];
+    ///     handler
+    ///         .send_data_chunked(CHARACTERISTIC_UUID, &firmware, ChunkWritePolicy::FastWithFinalAck)
+    ///         .await
+    ///         .unwrap();
+    /// });
+    /// ```
+    pub async fn send_data_chunked(
+        &self,
+        c: Uuid,
+        data: &[u8],
+        policy: ChunkWritePolicy,
+    ) -> Result<(), Error> {
+        let dev = self.connected_dev.lock().await;
+        let dev = dev.as_ref().ok_or(Error::NoDeviceConnected)?;
+        let state = self.state.lock().await;
+        let charac = state.get_charac(c)?;
+        let chunk_size = usize::from(state.max_payload());
+        let chunk_count = data.len().div_ceil(chunk_size);
+        // check all used write types before the first chunk is sent
+        if chunk_count > 1 {
+            check_write_type(charac, policy.write_type(false))?;
+        }
+        check_write_type(charac, policy.write_type(true))?;
+        for (i, chunk) in data.chunks(chunk_size).enumerate() {
+            let write_type = policy.write_type(i + 1 == chunk_count);
+            self.cancel_on_disconnect(dev.write(charac, chunk, write_type.into()))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Writes data that may be larger than the MTU in chunks and reports the progress
+    /// Chunks are sized like in [`Handler::send_data_chunked`] and all use `write_type`.
+    /// After each chunk the fraction of written bytes, between 0 and 1, is sent to `progress`.
+    /// # Errors
+    /// Returns an error if no device is connected or the characteristic is not available
+    /// or if writing any chunk fails
+    /// # Example
+    /// ```no_run
+    /// use tauri::async_runtime;
+    /// use tokio::sync::mpsc;
+    /// use uuid::{Uuid,uuid};
+    /// use tauri_plugin_blec::models::WriteType;
+    ///
+    /// const CHARACTERISTIC_UUID: Uuid = uuid!("51FF12BB-3ED8-46E5-B4F9-D64E2FEC021B");
+    /// async_runtime::block_on(async {
+    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///     let firmware = vec![0; 4096];
+    ///     let (tx, mut rx) = mpsc::channel(1);
+    ///     async_runtime::spawn(async move {
+    ///         while let Some(progress) = rx.recv().await {
+    ///             println!("{:.0}%", progress * 100.0);
+    ///         }
+    ///     });
+    ///     handler
+    ///         .send_data_with_progress(CHARACTERISTIC_UUID, &firmware, WriteType::WithResponse, tx)
+    ///         .await
+    ///         .unwrap();
+    /// });
+    /// ```
+    pub async fn send_data_with_progress(
+        &self,
+        c: Uuid,
+        data: &[u8],
+        write_type: models::WriteType,
+        progress: mpsc::Sender<f32>,
+    ) -> Result<(), Error> {
+        let dev = self.connected_dev.lock().await;
+        let dev = dev.as_ref().ok_or(Error::NoDeviceConnected)?;
+        let state = self.state.lock().await;
+        let charac = state.get_charac(c)?;
+        check_write_type(charac, write_type)?;
+        let chunk_size = usize::from(state.max_payload());
+        let mut written = 0;
+        for chunk in data.chunks(chunk_size) {
+            self.cancel_on_disconnect(dev.write(charac, chunk, write_type.into()))
+                .await?;
+            written += chunk.len();
+            #[allow(clippy::cast_precision_loss)]
+            let fraction = written as f32 / data.len() as f32;
+            // the transfer continues if nobody listens to the progress anymore
+            let _ = progress.send(fraction).await;
+        }
+        Ok(())
+    }
+
+    /// Performs the given writes strictly in order
+    /// The device stays locked for the whole sequence, so no other operation can run in
+    /// between. This is useful for devices that need an ordered configuration sequence.
+    /// # Errors
+    /// Stops at the first failing write and returns [`Error::WriteSequenceFailed`] with the
+    /// index of the failed step. Returns an error if no device is connected.
+    /// # Example
+    /// ```no_run
+    /// use tauri::async_runtime;
+    /// use uuid::{Uuid,uuid};
+    /// use tauri_plugin_blec::models::WriteType;
+    ///
+    /// const CHARACTERISTIC_UUID: Uuid = uuid!("51FF12BB-3ED8-46E5-B4F9-D64E2FEC021B");
+    /// async_runtime::block_on(async {
+    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///     handler.write_sequence(vec![
+    ///         (CHARACTERISTIC_UUID, vec![0x01, 0x10], WriteType::WithResponse),
+    ///         (CHARACTERISTIC_UUID, vec![0x02, 0x00], WriteType::WithResponse),
+    ///     ]).await.unwrap();
+    /// });
+    /// ```
+    pub async fn write_sequence(
+        &self,
+        ops: Vec<(Uuid, Vec<u8>, models::WriteType)>,
+    ) -> Result<(), Error> {
+        let dev = self.connected_dev.lock().await;
+        let dev = dev.as_ref().ok_or(Error::NoDeviceConnected)?;
+        let state = self.state.lock().await;
+        for (step, (c, data, write_type)) in ops.into_iter().enumerate() {
+            let res = match state
+                .get_charac(c)
+                .and_then(|charac| check_write_type(charac, write_type).map(|()| charac))
+            {
+                Ok(charac) => {
+                    self.cancel_on_disconnect(dev.write(charac, &data, write_type.into()))
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+            if let Err(e) = res {
+                return Err(Error::WriteSequenceFailed {
+                    step,
+                    source: Box::new(e),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Receives data from the given characteristic of the connected device
+    /// Returns the data as a vector of bytes
+    /// # Errors
+    /// Returns an error if no device is connected or the characteristic is not available,
+    /// [`Error::DisconnectedDuringOperation`] if the device disconnects during the read
+    /// or if the read operation fails
+    /// # Example
+    /// ```no_run
+    /// use tauri::async_runtime;
+    /// use uuid::{Uuid,uuid};
+    /// const CHARACTERISTIC_UUID: Uuid = uuid!("51FF12BB-3ED8-46E5-B4F9-D64E2FEC021B");
+    /// async_runtime::block_on(async {
+    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///     let response = handler.recv_data(CHARACTERISTIC_UUID).await.unwrap();
+    /// });
+    /// ```
+    pub async fn recv_data(&self, c: Uuid) -> Result<Vec<u8>, Error> {
+        self.read_charac(None, c).await
+    }
+
+    /// Receives data from the given characteristic of the given service
+    /// Use this if the device has the same characteristic UUID in several services, e.g. in
+    /// a primary and an included service. Otherwise this behaves like [`Handler::recv_data`]
+    /// # Errors
+    /// Returns an error if no device is connected or the service has no such characteristic
+    /// or if the read operation fails
+    pub async fn recv_data_in_service(&self, service: Uuid, c: Uuid) -> Result<Vec<u8>, Error> {
+        self.read_charac(Some(service), c).await
+    }
+
+    async fn read_charac(&self, service: Option<Uuid>, c: Uuid) -> Result<Vec<u8>, Error> {
+        let dev = self.connected_dev.lock().await;
+        let dev = dev.as_ref().ok_or(Error::NoDeviceConnected)?;
+        let state = self.state.lock().await;
+        let charac = state.find_charac(service, c)?;
+        let data = self.cancel_on_disconnect(dev.read(charac)).await?;
+        Ok(data)
+    }
+
+    /// Receives data from the characteristic with the given 16-bit UUID assigned by the
+    /// Bluetooth SIG
+    /// The short UUID is expanded with the Bluetooth base UUID, see [`Handler::recv_data`]
+    /// # Errors
+    /// Returns an error if no device is connected or the characteristic is not available
+    /// or if the read operation fails
+    /// # Example
+    /// ```no_run
+    /// use tauri::async_runtime;
+    /// async_runtime::block_on(async {
+    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///     let battery_level = handler.recv_data_short(0x2A19).await.unwrap();
+    /// });
+    /// ```
+    pub async fn recv_data_short(&self, c: u16) -> Result<Vec<u8>, Error> {
+        self.recv_data(uuid_from_u16(c)).await
+    }
+
+    /// Receives the value of the given characteristic starting at `offset`
+    /// This can be used to assemble long values manually. Neither Android nor the desktop
+    /// platforms expose ATT Read Blob requests with an explicit offset, they always perform the
+    /// blob reads for the full value themselves. So the full value is read and the part starting
+    /// at `offset` is returned.
+    /// # Errors
+    /// Returns an error if no device is connected or the characteristic is not available,
+    /// if the read operation fails or [`Error::InvalidOffset`] if `offset` is past the end
+    /// of the value
+    pub async fn recv_data_offset(&self, c: Uuid, offset: usize) -> Result<Vec<u8>, Error> {
+        let mut data = self.recv_data(c).await?;
+        if offset > data.len() {
+            return Err(Error::InvalidOffset {
+                offset,
+                len: data.len(),
+            });
+        }
+        Ok(data.split_off(offset))
+    }
+
+    /// Reads the given characteristic every `interval` to keep an otherwise idle connection
+    /// active, for devices or platforms that drop idle connections
+    /// Replaces a previously set keepalive. The keepalive ends when the device disconnects or
+    /// [`Handler::clear_keepalive`] is called, failed reads are only logged.
+    /// # Errors
+    /// Returns [`Error::KeepaliveIntervalTooShort`] if `interval` is shorter than 100ms,
+    /// or an error if no device is connected or the characteristic is not available
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tauri::async_runtime;
+    /// use uuid::{Uuid,uuid};
+    /// const BATTERY_LEVEL: Uuid = uuid!("00002A19-0000-1000-8000-00805F9B34FB");
+    /// async_runtime::block_on(async {
+    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///     handler.set_keepalive(BATTERY_LEVEL, Duration::from_secs(10)).await.unwrap();
+    /// });
+    /// ```
+    pub async fn set_keepalive(&'static self, c: Uuid, interval: Duration) -> Result<(), Error> {
+        if interval < MIN_KEEPALIVE_INTERVAL {
+            return Err(Error::KeepaliveIntervalTooShort(
+                MIN_KEEPALIVE_INTERVAL.as_millis(),
+            ));
+        }
+        let dev = self.connected_dev.lock().await;
+        dev.as_ref().ok_or(Error::NoDeviceConnected)?;
+        let mut state = self.state.lock().await;
+        state.get_charac(c)?;
+        let task = async_runtime::spawn(async move {
+            loop {
+                sleep(interval).await;
+                if let Err(e) = self.recv_data(c).await {
+                    debug!("keepalive read of {c} failed: {e}");
+                }
+            }
+        });
+        if let Some(previous) = state.keepalive_task.replace(task) {
+            previous.abort();
+        }
+        Ok(())
+    }
+
+    /// Stops the keepalive started with [`Handler::set_keepalive`]
+    pub async fn clear_keepalive(&self) {
+        if let Some(handle) = self.state.lock().await.keepalive_task.take() {
+            handle.abort();
+        }
+    }
+
+    /// Reads every readable characteristic of the given service of the connected device
+    /// Characteristics without the read property are skipped.
+    /// Returns the values by characteristic UUID
+    /// # Errors
+    /// Returns an error if no device is connected, [`Error::ServiceNotAvailable`] if the
+    /// device has no characteristics in the service or if one of the reads fails
+    /// # Example
+    /// ```no_run
+    /// use tauri::async_runtime;
+    /// use uuid::{Uuid,uuid};
+    /// const HEART_RATE_SERVICE: Uuid = uuid!("0000180D-0000-1000-8000-00805F9B34FB");
+    /// async_runtime::block_on(async {
+    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///     let values = handler.read_service(HEART_RATE_SERVICE).await.unwrap();
+    /// });
+    /// ```
+    pub async fn read_service(&self, service: Uuid) -> Result<HashMap<Uuid, Vec<u8>>, Error> {
+        let dev = self.connected_dev.lock().await;
+        let dev = dev.as_ref().ok_or(Error::NoDeviceConnected)?;
+        let characs: Vec<Characteristic> = self
+            .state
+            .lock()
+            .await
+            .characs
+            .iter()
+            .filter(|c| c.service_uuid == service)
+            .cloned()
+            .collect();
+        if characs.is_empty() {
+            return Err(Error::ServiceNotAvailable(service.to_string()));
+        }
+        let mut values = HashMap::new();
+        for c in characs
+            .iter()
+            .filter(|c| c.properties.contains(CharPropFlags::READ))
+        {
+            values.insert(c.uuid, self.cancel_on_disconnect(dev.read(c)).await?);
+        }
+        Ok(values)
+    }
+
+    /// Reads the given characteristics concurrently and returns the result of each read
+    /// A characteristic that is not available or fails to read only fails its own entry.
+    /// Platforms that run GATT operations one after another, like Android, still return all
+    /// results but take as long as reading one by one.
+    /// # Errors
+    /// Returns an error if no device is connected
+    pub async fn read_parallel(
+        &self,
+        uuids: &[Uuid],
+    ) -> Result<HashMap<Uuid, Result<Vec<u8>, Error>>, Error> {
+        let dev = self.connected_dev.lock().await;
+        let dev = dev.as_ref().ok_or(Error::NoDeviceConnected)?;
+        let characs: Vec<(Uuid, Result<Characteristic, Error>)> = {
+            let state = self.state.lock().await;
+            uuids
+                .iter()
+                .map(|uuid| (*uuid, state.get_charac(*uuid).cloned()))
+                .collect()
+        };
+        let reads = characs.into_iter().map(|(uuid, charac)| async move {
+            let value = match charac {
+                Ok(charac) => self.cancel_on_disconnect(dev.read(&charac)).await,
+                Err(e) => Err(e),
+            };
+            (uuid, value)
+        });
+        Ok(join_all(reads).await.into_iter().collect())
+    }
+
+    /// Receives data from the given characteristic and parses it as a fixed width number
+    /// # Errors
+    /// Returns an error if no device is connected or the characteristic is not available,
+    /// if the read operation fails or if the value does not have the size of `T`
+    /// # Example
+    /// ```no_run
+    /// use tauri::async_runtime;
+    /// use uuid::{Uuid,uuid};
+    /// use tauri_plugin_blec::models::Endianness;
+    ///
+    /// const CHARACTERISTIC_UUID: Uuid = uuid!("51FF12BB-3ED8-46E5-B4F9-D64E2FEC021B");
+    /// async_runtime::block_on(async {
+    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///     let value: u32 = handler.recv_as(CHARACTERISTIC_UUID, Endianness::Little).await.unwrap();
+    /// });
+    /// ```
+    pub async fn recv_as<T: FromBleBytes>(
+        &self,
+        c: Uuid,
+        endianness: Endianness,
+    ) -> Result<T, Error> {
+        let data = self.recv_data(c).await?;
+        T::from_ble_bytes(&data, endianness).ok_or(Error::InvalidValueLength {
+            expected: std::mem::size_of::<T>(),
+            actual: data.len(),
+        })
+    }
+
+    /// Reads the GAP Appearance characteristic (0x2A01) of the connected device
+    /// The value describes the device category, e.g. `0x0340` for a heart rate sensor
+    /// # Errors
+    /// Returns an error if no device is connected, if the device has no appearance
+    /// characteristic or if the read operation fails
+    pub async fn appearance(&self) -> Result<u16, Error> {
+        self.recv_as(APPEARANCE_UUID, Endianness::Little).await
+    }
+
+    /// Reads the PnP ID characteristic (0x2A50) of the connected device
+    /// # Errors
+    /// Returns an error if no device is connected, if the device has no PnP ID
+    /// characteristic, if the read operation fails or if the value is malformed
+    pub async fn pnp_id(&self) -> Result<PnpId, Error> {
+        let data = self.recv_data(PNP_ID_UUID).await?;
+        PnpId::from_bytes(&data).ok_or(Error::InvalidValueLength {
+            expected: PnpId::SIZE,
+            actual: data.len(),
+        })
+    }
+
+    /// Reads the Peripheral Preferred Connection Parameters characteristic (0x2A04) of the
+    /// connected device
+    /// # Errors
+    /// Returns an error if no device is connected, if the device has no preferred connection
+    /// parameters characteristic, if the read operation fails or if the value is malformed
+    pub async fn preferred_connection_parameters(&self) -> Result<PreferredConnParams, Error> {
+        let data = self.recv_data(PREFERRED_CONN_PARAMS_UUID).await?;
+        PreferredConnParams::from_bytes(&data).ok_or(Error::InvalidValueLength {
+            expected: PreferredConnParams::SIZE,
+            actual: data.len(),
+        })
+    }
+
+    /// Reads the Firmware Revision String characteristic (0x2A26) of the device information
+    /// service, e.g. to check if a firmware update is needed
+    /// Invalid UTF-8 is replaced instead of failing the read
+    /// # Errors
+    /// Returns an error if no device is connected, if the device has no firmware revision
+    /// characteristic or if the read operation fails
+    pub async fn firmware_revision(&self) -> Result<String, Error> {
+        let data = self.recv_data(FIRMWARE_REVISION_UUID).await?;
+        Ok(String::from_utf8_lossy(&data).into_owned())
+    }
+
+    /// Writes data to the given characteristic and reads it back right after
+    /// The device stays locked for both operations, so no other operation can run between
+    /// the write and the read. This is useful for devices where a register address is written
+    /// to a characteristic and the register value is read from the same characteristic.
+    /// # Errors
+    /// Returns an error if no device is connected or the characteristic is not available
+    /// or if the write or read operation fails
+    /// # Example
+    /// ```no_run
+    /// use tauri::async_runtime;
+    /// use uuid::{Uuid,uuid};
+    /// use tauri_plugin_blec::models::WriteType;
+    ///
+    /// const CHARACTERISTIC_UUID: Uuid = uuid!("51FF12BB-3ED8-46E5-B4F9-D64E2FEC021B");
+    /// async_runtime::block_on(async {
+    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///     let register = [0x10];
+    ///     let value = handler.write_then_read(CHARACTERISTIC_UUID, &register, WriteType::WithResponse).await.unwrap();
+    /// });
+    /// ```
+    pub async fn write_then_read(
+        &self,
+        c: Uuid,
+        data: &[u8],
+        write_type: models::WriteType,
+    ) -> Result<Vec<u8>, Error> {
+        let dev = self.connected_dev.lock().await;
+        let dev = dev.as_ref().ok_or(Error::NoDeviceConnected)?;
+        let state = self.state.lock().await;
+        let charac = state.get_charac(c)?;
+        check_write_type(charac, write_type)?;
+        self.cancel_on_disconnect(dev.write(charac, data, write_type.into()))
+            .await?;
+        let data = self.cancel_on_disconnect(dev.read(charac)).await?;
+        Ok(data)
+    }
+
+    /// Writes data to `write_c` and waits for the device to echo it back as a notification on
+    /// `notify_c`
+    /// Notifications are enabled on `notify_c` for the check if they are not already and are
+    /// disabled again afterwards. This is a common reliability pattern for devices that don't
+    /// support writes with response.
+    /// # Errors
+    /// Returns [`Error::VerificationFailed`] if the echoed value doesn't match the written data,
+    /// [`Error::Timeout`] if no notification arrives within `timeout`,
+    /// or an error if no device is connected, a characteristic is not available or the write fails
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tauri::async_runtime;
+    /// use uuid::{Uuid,uuid};
+    /// use tauri_plugin_blec::models::WriteType;
+    ///
+    /// const COMMAND_UUID: Uuid = uuid!("51FF12BB-3ED8-46E5-B4F9-D64E2FEC021B");
+    /// const ECHO_UUID: Uuid = uuid!("51FF12BB-3ED8-46E5-B4F9-D64E2FEC021C");
+    /// async_runtime::block_on(async {
+    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///     handler
+    ///         .write_verified(
+    ///             COMMAND_UUID,
+    ///             ECHO_UUID,
+    ///             &[0x01, 0x02],
+    ///             WriteType::WithoutResponse,
+    ///             Duration::from_secs(1),
+    ///         )
+    ///         .await
+    ///         .unwrap();
+    /// });
+    /// ```
+    pub async fn write_verified(
+        &self,
+        write_c: Uuid,
+        notify_c: Uuid,
+        data: &[u8],
+        write_type: models::WriteType,
+        timeout: Duration,
+    ) -> Result<(), Error> {
+        // start listening before writing to not miss a fast echo
+        let mut probe = self.start_probe(notify_c).await?;
+        let echo = match self.send_data(write_c, data, write_type).await {
+            Ok(()) => tokio::time::timeout(timeout, probe.values.recv())
+                .await
+                .map_err(|_| Error::Timeout),
+            Err(e) => Err(e),
+        };
+        let notify_uuid = probe.uuid;
+        self.end_probe(probe, false).await;
+        match echo? {
+            Some(echo) if echo == data => Ok(()),
+            Some(echo) => {
+                debug!("echo on {notify_uuid} did not match: {echo:?}");
+                Err(Error::VerificationFailed)
+            }
+            None => Err(Error::NoDeviceConnected),
+        }
+    }
+
+    /// Registers a temporary listener for `c` and enables notifications if no other listener
+    /// did already
+    async fn start_probe(&self, c: Uuid) -> Result<NotificationProbe, Error> {
+        let (tx, values) = mpsc::unbounded_channel();
+        let mut listener = Listener::new(
+            c,
+            SubscriptionHandler::from_async(move |data| {
+                let _ = tx.send(data);
+                Box::pin(async {})
+            }),
+        );
+        listener.temporary = true;
+        let id = listener.id;
+        let was_subscribed = self
+            .notify_listeners
+            .lock()
+            .await
+            .iter()
+            .any(|l| l.uuid == c && !l.temporary);
+        self.push_listener(listener).await?;
+        let probe = NotificationProbe {
+            id,
+            uuid: c,
+            was_subscribed,
+            values,
+        };
+        if !was_subscribed {
+            if let Err(e) = self.enable_notifications(c).await {
+                self.end_probe(probe, true).await;
+                return Err(e);
+            }
+        }
+        Ok(probe)
+    }
+
+    /// Removes the listener of the probe and disables notifications again if they were only
+    /// enabled for the probe, unless `keep_enabled` is set
+    /// Notifications enabled with [`Handler::enable_notifications`] but without a listener
+    /// are disabled as well
+    async fn end_probe(&self, probe: NotificationProbe, keep_enabled: bool) {
+        let still_used = {
+            let mut listeners = self.notify_listeners.lock().await;
+            let registered = listeners.iter().any(|l| l.id == probe.id);
+            listeners.retain(|l| l.id != probe.id);
+            // on disconnect all listeners are removed already
+            !registered || listeners.iter().any(|l| l.uuid == probe.uuid)
+        };
+        if keep_enabled || probe.was_subscribed || still_used {
+            return;
+        }
+        if let Err(e) = self.disable_notifications(probe.uuid).await {
+            warn!("Failed to disable notifications for {}: {e}", probe.uuid);
+        }
+    }
+
+    /// Enables notifications for the given characteristic and waits for the first value,
+    /// e.g. to confirm that a sensor is alive
+    /// Notifications stay enabled on success, use [`Handler::add_listener`] to receive further
+    /// values. If no notification arrives within `timeout`, notifications are disabled again
+    /// unless another subscription uses them.
+    /// # Errors
+    /// Returns [`Error::Timeout`] if no notification was received in time, or an error if no
+    /// device is connected, the characteristic is not available or subscribing fails
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tauri::async_runtime;
+    /// use uuid::{Uuid,uuid};
+    /// const CHARACTERISTIC_UUID: Uuid = uuid!("51FF12BB-3ED8-46E5-B4F9-D64E2FEC021B");
+    /// async_runtime::block_on(async {
+    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///     let first = handler
+    ///         .subscribe_await_first(CHARACTERISTIC_UUID, Duration::from_secs(2))
+    ///         .await
+    ///         .unwrap();
+    ///     println!("sensor is alive: {first:?}");
+    /// });
+    /// ```
+    pub async fn subscribe_await_first(
+        &self,
+        c: Uuid,
+        timeout: Duration,
+    ) -> Result<Vec<u8>, Error> {
+        let mut probe = self.start_probe(c).await?;
+        let first = tokio::time::timeout(timeout, probe.values.recv()).await;
+        self.end_probe(probe, matches!(first, Ok(Some(_)))).await;
+        match first {
+            Ok(Some(value)) => Ok(value),
+            Ok(None) => Err(Error::NoDeviceConnected),
+            Err(_) => Err(Error::Timeout),
+        }
+    }
+
+    /// Runs a round trip test against a device that echoes written data
+    /// Writes `size` random bytes to `write_c` and receives them back from `echo_c`.
+    /// If `echo_c` supports notifications the echo is awaited as a notification through a
+    /// temporary listener, otherwise it is read after the write. Both can be the same
+    /// characteristic.
+    /// # Errors
+    /// Returns [`Error::VerificationFailed`] if the received data doesn't match,
+    /// [`Error::Timeout`] if no notification arrives in time,
+    /// or an error if no device is connected, a characteristic is not available or an operation fails
+    /// # Example
+    /// ```no_run
+    /// use tauri::async_runtime;
+    /// use uuid::{Uuid,uuid};
+    ///
+    /// const CHARACTERISTIC_UUID: Uuid = uuid!("51FF12BB-3ED8-46E5-B4F9-D64E2FEC021B");
+    /// async_runtime::block_on(async {
+    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///     let stats = handler.loopback_test(CHARACTERISTIC_UUID, CHARACTERISTIC_UUID, 500).await.unwrap();
+    ///     println!("{} bytes/s", stats.bytes_per_second);
+    /// });
+    /// ```
+    pub async fn loopback_test(
+        &self,
+        write_c: Uuid,
+        echo_c: Uuid,
+        size: usize,
+    ) -> Result<LoopbackStats, Error> {
+        let data = random_bytes(size);
+        let echo_notifies = {
+            let state = self.state.lock().await;
+            state
+                .get_charac(echo_c)?
+                .properties
+                .intersects(CharPropFlags::NOTIFY | CharPropFlags::INDICATE)
+        };
+        // prefer writes without response, the echo confirms the data anyway
+        let write_type = self
+            .supported_write_types(write_c)
+            .await?
+            .last()
+            .copied()
+            .unwrap_or(models::WriteType::WithoutResponse);
+        let start = Instant::now();
+        if echo_notifies {
+            self.write_verified(write_c, echo_c, &data, write_type, LOOPBACK_TIMEOUT)
+                .await?;
+        } else {
+            self.send_data(write_c, &data, write_type).await?;
+            if self.recv_data(echo_c).await? != data {
+                return Err(Error::VerificationFailed);
+            }
+        }
+        let elapsed = start.elapsed();
+        Ok(LoopbackStats {
+            bytes: size,
+            round_trip_ms: elapsed.as_secs_f64() * 1000.0,
+            #[allow(clippy::cast_precision_loss)]
+            bytes_per_second: size as f64 / elapsed.as_secs_f64(),
+        })
+    }
+
+    /// Subscribe to notifications from the given characteristic
+    /// The callback will be called whenever a notification is received
+    /// # Errors
+    /// Returns an error if no device is connected or the characteristic is not available
+    /// or if the subscribe operation fails
+    /// # Example
+    /// ```no_run
+    /// use tauri::async_runtime;
+    /// use uuid::{Uuid,uuid};
+    /// const CHARACTERISTIC_UUID: Uuid = uuid!("51FF12BB-3ED8-46E5-B4F9-D64E2FEC021B");
+    /// async_runtime::block_on(async {
+    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///     let response = handler.subscribe(CHARACTERISTIC_UUID,|data| println!("received {data:?}")).await.unwrap();
+    /// });
+    /// ```
+    pub async fn subscribe(
+        &self,
+        c: Uuid,
+        callback: impl Into<SubscriptionHandler>,
+    ) -> Result<(), Error> {
+        self.subscribe_listener(Listener::new(c, callback.into()))
+            .await
+    }
+
+    /// Subscribe to notifications from the characteristic with the given 16-bit UUID assigned
+    /// by the Bluetooth SIG
+    /// The short UUID is expanded with the Bluetooth base UUID, see [`Handler::subscribe`]
+    /// # Errors
+    /// Returns an error if no device is connected or the characteristic is not available
+    /// or if the subscribe operation fails
+    pub async fn subscribe_short(
+        &self,
+        c: u16,
+        callback: impl Into<SubscriptionHandler>,
+    ) -> Result<(), Error> {
+        self.subscribe(uuid_from_u16(c), callback).await
+    }
+
+    /// Subscribe to notifications from the given characteristic, waiting for a connection if
+    /// needed
+    /// If no device is connected yet or services are still being discovered, the subscription
+    /// is queued and applied once the next connection is ready. Otherwise this behaves like
+    /// [`Handler::subscribe`]. Failures of queued subscriptions are only logged.
+    /// # Errors
+    /// Returns an error if a device is connected and the characteristic is not available
+    /// or if the subscribe operation fails
+    pub async fn subscribe_when_ready(
+        &self,
+        c: Uuid,
+        callback: impl Into<SubscriptionHandler>,
+    ) -> Result<(), Error> {
+        let listener = Listener::new(c, callback.into());
+        {
+            let mut state = self.state.lock().await;
+            if state.characs.is_empty() {
+                debug!("no services available yet, queueing subscription to {c}");
+                state.pending_listeners.push(listener);
+                return Ok(());
+            }
+        }
+        self.subscribe_listener(listener).await
+    }
+
+    /// Subscribe to notifications from the given characteristic, calling the callback at most
+    /// once per `min_interval`
+    /// All notifications are still received from the device, but only the latest value is
+    /// passed to the callback once the interval has passed. Values in between are dropped.
+    /// # Errors
+    /// Returns an error if no device is connected or the characteristic is not available
+    /// or if the subscribe operation fails
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tauri::async_runtime;
+    /// use uuid::{Uuid,uuid};
+    /// const CHARACTERISTIC_UUID: Uuid = uuid!("51FF12BB-3ED8-46E5-B4F9-D64E2FEC021B");
+    /// async_runtime::block_on(async {
+    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///     // update the UI with at most 10Hz
+    ///     handler
+    ///         .subscribe_throttled(CHARACTERISTIC_UUID, Duration::from_millis(100), |data| println!("received {data:?}"))
+    ///         .await
+    ///         .unwrap();
+    /// });
+    /// ```
+    pub async fn subscribe_throttled(
+        &self,
+        c: Uuid,
+        min_interval: Duration,
+        callback: impl Into<SubscriptionHandler>,
+    ) -> Result<(), Error> {
+        let mut listener = Listener::new(c, callback.into());
+        listener.throttle = Some(Throttle::new(min_interval, listener.callback.clone()));
+        self.subscribe_listener(listener).await
+    }
+
+    /// Subscribe to notifications from the given characteristic, only calling the callback for
+    /// notifications that match the predicate
+    /// This is useful for characteristics that multiplex different packets, e.g. by an opcode byte
+    /// # Errors
+    /// Returns an error if no device is connected or the characteristic is not available
+    /// or if the subscribe operation fails
+    /// # Example
+    /// ```no_run
+    /// use tauri::async_runtime;
+    /// use uuid::{Uuid,uuid};
+    /// const CHARACTERISTIC_UUID: Uuid = uuid!("51FF12BB-3ED8-46E5-B4F9-D64E2FEC021B");
+    /// async_runtime::block_on(async {
+    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///     handler
+    ///         .subscribe_filtered(
+    ///             CHARACTERISTIC_UUID,
+    ///             |data| data.first() == Some(&0x01),
+    ///             |data| println!("received {data:?}"),
+    ///         )
+    ///         .await
+    ///         .unwrap();
+    /// });
+    /// ```
+    pub async fn subscribe_filtered(
+        &self,
+        c: Uuid,
+        predicate: impl Fn(&[u8]) -> bool + Send + Sync + 'static,
+        callback: impl Into<SubscriptionHandler>,
+    ) -> Result<(), Error> {
+        let mut listener = Listener::new(c, callback.into());
+        listener.filter = Some(Box::new(predicate));
+        self.subscribe_listener(listener).await
+    }
+
+    /// Subscribe to notifications from the given characteristic, calling the callback only
+    /// when the value differs from the previous one
+    /// Repeated notifications with identical bytes are dropped, the first notification is
+    /// always delivered.
+    /// # Errors
+    /// Returns an error if no device is connected or the characteristic is not available
+    /// or if the subscribe operation fails
+    /// # Example
+    /// ```no_run
+    /// use tauri::async_runtime;
+    /// use uuid::{Uuid,uuid};
+    /// const CHARACTERISTIC_UUID: Uuid = uuid!("51FF12BB-3ED8-46E5-B4F9-D64E2FEC021B");
+    /// async_runtime::block_on(async {
+    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///     handler
+    ///         .subscribe_on_change(CHARACTERISTIC_UUID, |data| println!("changed to {data:?}"))
+    ///         .await
+    ///         .unwrap();
+    /// });
+    /// ```
+    pub async fn subscribe_on_change(
+        &self,
+        c: Uuid,
+        callback: impl Into<SubscriptionHandler>,
+    ) -> Result<(), Error> {
+        let mut listener = Listener::new(c, callback.into());
+        listener.only_changed = true;
+        self.subscribe_listener(listener).await
+    }
+
+    /// Subscribe to notifications from the given characteristic and reassemble messages that
+    /// the device splits across multiple notifications
+    /// The callback receives complete messages as described by the [`Framing`]. Incomplete
+    /// messages are buffered, if more than 64 KiB accumulate without completing a message the
+    /// buffer is dropped.
+    /// # Errors
+    /// Returns [`Error::InvalidFraming`] if the framing is invalid, an error if no device is
+    /// connected or the characteristic is not available or if the subscribe operation fails
+    /// # Example
+    /// ```no_run
+    /// use tauri::async_runtime;
+    /// use uuid::{Uuid,uuid};
+    /// use tauri_plugin_blec::models::Framing;
+    ///
+    /// const CHARACTERISTIC_UUID: Uuid = uuid!("51FF12BB-3ED8-46E5-B4F9-D64E2FEC021B");
+    /// async_runtime::block_on(async {
+    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
+    ///     // one byte message type followed by a two byte payload length
+    ///     let framing = Framing::LengthPrefixed { header_len: 3, length_offset: 1, length_size: 2 };
+    ///     handler
+    ///         .subscribe_reassembled(CHARACTERISTIC_UUID, framing, |message| println!("received {message:?}"))
+    ///         .await
+    ///         .unwrap();
+    /// });
+    /// ```
+    pub async fn subscribe_reassembled(
+        &self,
+        c: Uuid,
+        framing: Framing,
+        callback: impl Into<SubscriptionHandler>,
+    ) -> Result<(), Error> {
+        if !framing.is_valid() {
+            return Err(Error::InvalidFraming);
+        }
+        let mut listener = Listener::new(c, callback.into());
+        listener.reassembler = Some(Reassembler::new(framing));
+        self.subscribe_listener(listener).await
+    }
+
+    async fn subscribe_listener(&self, listener: Listener) -> Result<(), Error> {
+        self.enable_notifications(listener.uuid).await?;
+        self.push_listener(listener).await
+    }
+
+    /// Unsubscribe from notifications for the given characteristic
+    /// This will also remove the callback from the list of listeners
+    /// # Errors
+    /// Returns an error if no device is connected or the characteristic is not available
+    /// or if the unsubscribe operation fails
+    pub async fn unsubscribe(&self, c: Uuid) -> Result<(), Error> {
+        self.disable_notifications(c).await?;
+        self.remove_listener(c).await
+    }
+
+    /// Enables notifications for the given characteristic on the device by writing its CCCD,
+    /// without registering a callback. Use [`Handler::add_listener`] to receive the values.
+    /// [`Handler::subscribe`] does both at once
+    /// # Errors
+    /// Returns an error if no device is connected or the characteristic is not available
+    /// or if the subscribe operation fails
+    pub async fn enable_notifications(&self, c: Uuid) -> Result<(), Error> {
+        let (retries, delay) = self.state.lock().await.subscribe_retries;
+        let mut attempt = 0;
+        loop {
+            match self.subscribe_charac(c).await {
+                Err(Error::Btleplug(e)) if attempt < retries && is_transient(&e) => {
+                    attempt += 1;
+                    warn!("Failed to enable notifications for {c}, retry {attempt}/{retries}: {e}");
+                    // don't block other operations while waiting
+                    sleep(delay).await;
+                }
+                res => return res,
+            }
+        }
+    }
+
+    async fn subscribe_charac(&self, c: Uuid) -> Result<(), Error> {
+        let dev = self.connected_dev.lock().await;
+        let dev = dev.as_ref().ok_or(Error::NoDeviceConnected)?;
+        let state = self.state.lock().await;
+        let charac = state.get_charac(c)?;
+        self.cancel_on_disconnect(dev.subscribe(charac)).await
+    }
+
+    /// Disables notifications for the given characteristic on the device by writing its CCCD.
+    /// Registered callbacks are kept but won't receive any values until notifications are
+    /// enabled again
+    /// # Errors
+    /// Returns an error if no device is connected or the characteristic is not available
+    /// or if the unsubscribe operation fails
+    pub async fn disable_notifications(&self, c: Uuid) -> Result<(), Error> {
+        let dev = self.connected_dev.lock().await;
+        let dev = dev.as_ref().ok_or(Error::NoDeviceConnected)?;
+        let state = self.state.lock().await;
+        let charac = state.get_charac(c)?;
+        dev.unsubscribe(charac).await?;
+        Ok(())
+    }
+
+    /// Registers a callback for notifications of the given characteristic without enabling
+    /// notifications on the device, see [`Handler::enable_notifications`]
+    /// # Errors
+    /// Returns an error if no device is connected or the characteristic is not available
+    pub async fn add_listener(
+        &self,
+        c: Uuid,
+        callback: impl Into<SubscriptionHandler>,
+    ) -> Result<(), Error> {
+        self.push_listener(Listener::new(c, callback.into())).await
+    }
+
+    /// Removes all callbacks of the given characteristic while notifications stay enabled on
+    /// the device. Useful for stateful devices that must keep notifying
+    /// # Errors
+    /// Returns an error if no device is connected or the characteristic is not available
+    pub async fn remove_listener(&self, c: Uuid) -> Result<(), Error> {
+        let dev = self.connected_dev.lock().await;
+        dev.as_ref().ok_or(Error::NoDeviceConnected)?;
+        let state = self.state.lock().await;
+        let charac = state.get_charac(c)?;
+        let mut listeners = self.notify_listeners.lock().await;
+        listeners.retain(|l| l.uuid != charac.uuid);
+        Ok(())
+    }
+
+    async fn push_listener(&self, mut listener: Listener) -> Result<(), Error> {
+        let dev = self.connected_dev.lock().await;
+        dev.as_ref().ok_or(Error::NoDeviceConnected)?;
+        let state = self.state.lock().await;
+        let charac = state.get_charac(listener.uuid)?;
+        listener.uuid = charac.uuid;
+        self.notify_listeners.lock().await.push(listener);
+        Ok(())
+    }
+
+    /// Stops delivering notifications for the given characteristic to its callbacks without
+    /// unsubscribing on the device
+    /// Only the latest value received while paused is kept and delivered on
+    /// [`Handler::resume_notifications`]. This avoids writing the CCCD again, e.g. when the app
+    /// goes to the background.
+    /// # Errors
+    /// Returns an error if no device is connected or the characteristic is not available
+    pub async fn pause_notifications(&self, c: Uuid) -> Result<(), Error> {
+        self.set_notifications_paused(c, true).await
+    }
+
+    /// Resumes notifications paused with [`Handler::pause_notifications`]
+    /// If a notification was received while paused, the latest value is delivered right away
+    /// # Errors
+    /// Returns an error if no device is connected or the characteristic is not available
+    pub async fn resume_notifications(&self, c: Uuid) -> Result<(), Error> {
+        self.set_notifications_paused(c, false).await
+    }
+
+    async fn set_notifications_paused(&self, c: Uuid, paused: bool) -> Result<(), Error> {
+        let dev = self.connected_dev.lock().await;
+        dev.as_ref().ok_or(Error::NoDeviceConnected)?;
+        let state = self.state.lock().await;
+        let charac = state.get_charac(c)?;
+        let mut listeners = self.notify_listeners.lock().await;
+        for l in listeners.iter_mut().filter(|l| l.uuid == charac.uuid) {
+            l.paused = paused;
+            if let Some(data) = l.last_value.take() {
+                if !paused {
+                    match &l.throttle {
+                        Some(throttle) => throttle.push(data),
+                        None => {
+                            tokio::task::spawn(l.callback.clone().run(data));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the time since the last notification was received on the given characteristic.
+    /// Can be used as a health check to detect subscriptions that no longer deliver data.
+    /// Returns `None` if there is no subscription or no notification was received yet
+    pub async fn notification_age(&self, c: Uuid) -> Option<Duration> {
+        self.notify_listeners
+            .lock()
+            .await
+            .iter()
+            .filter(|l| l.uuid == c)
+            .filter_map(|l| l.last_notification_at)
+            .max()
+            .map(|at| at.elapsed())
+    }
+
+    /// Returns all characteristics with active subscriptions together with the last received
+    /// value and the time since it was received, to check the health of all notifications
+    /// at once
+    pub async fn subscriptions(&self) -> Vec<SubscriptionInfo> {
+        let listeners = self.notify_listeners.lock().await;
+        let mut subscriptions: Vec<SubscriptionInfo> = vec![];
+        for l in listeners.iter().filter(|l| !l.temporary) {
+            // all listeners of a characteristic receive the same notifications
+            if subscriptions.iter().any(|s| s.uuid == l.uuid) {
+                continue;
+            }
+            subscriptions.push(SubscriptionInfo {
+                uuid: l.uuid,
+                last_value: l.last_received.clone(),
+                last_notification_ms_ago: l
+                    .last_notification_at
+                    .map(|at| u64::try_from(at.elapsed().as_millis()).unwrap_or(u64::MAX)),
+            });
+        }
+        subscriptions
+    }
+
+    /// Reads the values of all descriptors of the given characteristic
+    /// On Android all descriptors are read with a single call to the plugin,
+    /// on other platforms they are read one after another
+    /// # Errors
+    /// Returns an error if no device is connected or the characteristic is not available
+    /// or if reading any of the descriptors fails
+    pub async fn read_descriptors(&self, c: Uuid) -> Result<Vec<(Uuid, Vec<u8>)>, Error> {
+        let dev = self.connected_dev.lock().await;
+        let dev = dev.as_ref().ok_or(Error::NoDeviceConnected)?;
+        let state = self.state.lock().await;
+        let charac = state.get_charac(c)?;
+        #[cfg(target_os = "android")]
+        {
+            use crate::android::DescriptorExt;
+            self.cancel_on_disconnect(dev.read_descriptors(charac))
+                .await
+        }
+        #[cfg(not(target_os = "android"))]
+        {
+            let mut values = Vec::with_capacity(charac.descriptors.len());
+            for d in &charac.descriptors {
+                values.push((
+                    d.uuid,
+                    self.cancel_on_disconnect(dev.read_descriptor(d)).await?,
+                ));
+            }
+            Ok(values)
+        }
+    }
+
+    /// Reads the value of the given characteristic and of all its descriptors at once, e.g. to
+    /// show a characteristic node in a GATT explorer
+    /// The value is only read if the characteristic is readable. Failing reads of the value or
+    /// single descriptors are left out instead of failing the whole inspection
+    /// # Errors
+    /// Returns an error if no device is connected or the characteristic is not available
+    pub async fn inspect_characteristic(&self, c: Uuid) -> Result<CharacteristicInspection, Error> {
         let dev = self.connected_dev.lock().await;
         let dev = dev.as_ref().ok_or(Error::NoDeviceConnected)?;
         let state = self.state.lock().await;
         let charac = state.get_charac(c)?;
-        dev.write(charac, data, write_type.into()).await?;
-        Ok(())
+        let value = if charac.properties.contains(CharPropFlags::READ) {
+            match self.cancel_on_disconnect(dev.read(charac)).await {
+                Ok(value) => Some(value),
+                Err(Error::DisconnectedDuringOperation) => {
+                    return Err(Error::DisconnectedDuringOperation)
+                }
+                Err(e) => {
+                    debug!("Failed to read {c} for inspection: {e}");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        let mut descriptors = Vec::with_capacity(charac.descriptors.len());
+        for d in &charac.descriptors {
+            match self.cancel_on_disconnect(dev.read_descriptor(d)).await {
+                Ok(value) => descriptors.push((d.uuid, value)),
+                Err(Error::DisconnectedDuringOperation) => {
+                    return Err(Error::DisconnectedDuringOperation)
+                }
+                Err(e) => debug!("Failed to read descriptor {} of {c}: {e}", d.uuid),
+            }
+        }
+        Ok(CharacteristicInspection {
+            uuid: charac.uuid,
+            properties: models::get_flags(charac.properties),
+            value,
+            descriptors,
+        })
     }
 
-    /// Receives data from the given characteristic of the connected device
-    /// Returns the data as a vector of bytes
+    /// Reads the value of a single descriptor of the given characteristic
     /// # Errors
-    /// Returns an error if no device is connected or the characteristic is not available
-    /// or if the read operation fails
-    /// # Example
-    /// ```no_run
-    /// use tauri::async_runtime;
-    /// use uuid::{Uuid,uuid};
-    /// const CHARACTERISTIC_UUID: Uuid = uuid!("51FF12BB-3ED8-46E5-B4F9-D64E2FEC021B");
-    /// async_runtime::block_on(async {
-    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
-    ///     let response = handler.recv_data(CHARACTERISTIC_UUID).await.unwrap();
-    /// });
-    /// ```
-    pub async fn recv_data(&self, c: Uuid) -> Result<Vec<u8>, Error> {
+    /// Returns an error if no device is connected, the characteristic or descriptor is not
+    /// available or if the read operation fails
+    pub async fn read_descriptor(&self, c: Uuid, d: Uuid) -> Result<Vec<u8>, Error> {
         let dev = self.connected_dev.lock().await;
         let dev = dev.as_ref().ok_or(Error::NoDeviceConnected)?;
         let state = self.state.lock().await;
-        let charac = state.get_charac(c)?;
-        let data = dev.read(charac).await?;
-        Ok(data)
+        let descriptor = state.get_descriptor(c, d)?;
+        self.cancel_on_disconnect(dev.read_descriptor(descriptor))
+            .await
     }
 
-    /// Subscribe to notifications from the given characteristic
-    /// The callback will be called whenever a notification is received
+    /// Reads the Characteristic User Description descriptor (0x2901) of the given characteristic
+    /// Returns `None` if the characteristic has no description. Invalid UTF-8 is replaced with
+    /// the replacement character.
     /// # Errors
-    /// Returns an error if no device is connected or the characteristic is not available
-    /// or if the subscribe operation fails
-    /// # Example
-    /// ```no_run
-    /// use tauri::async_runtime;
-    /// use uuid::{Uuid,uuid};
-    /// const CHARACTERISTIC_UUID: Uuid = uuid!("51FF12BB-3ED8-46E5-B4F9-D64E2FEC021B");
-    /// async_runtime::block_on(async {
-    ///     let handler = tauri_plugin_blec::get_handler().unwrap();
-    ///     let response = handler.subscribe(CHARACTERISTIC_UUID,|data| println!("received {data:?}")).await.unwrap();
-    /// });
-    /// ```
-    pub async fn subscribe(
+    /// Returns an error if no device is connected, the characteristic is not available
+    /// or if the read operation fails
+    pub async fn characteristic_description(&self, c: Uuid) -> Result<Option<String>, Error> {
+        let has_description = {
+            let dev = self.connected_dev.lock().await;
+            dev.as_ref().ok_or(Error::NoDeviceConnected)?;
+            let state = self.state.lock().await;
+            state
+                .get_charac(c)?
+                .descriptors
+                .iter()
+                .any(|d| d.uuid == USER_DESCRIPTION_UUID)
+        };
+        if !has_description {
+            return Ok(None);
+        }
+        let data = self.read_descriptor(c, USER_DESCRIPTION_UUID).await?;
+        Ok(Some(String::from_utf8_lossy(&data).into_owned()))
+    }
+
+    /// Writes the Characteristic User Description descriptor (0x2901) of the given
+    /// characteristic. Only works if the device allows writing the description
+    /// # Errors
+    /// Returns an error if no device is connected, the characteristic has no description
+    /// or if the write operation fails
+    pub async fn set_characteristic_description(
         &self,
         c: Uuid,
-        callback: impl Into<SubscriptionHandler>,
+        description: &str,
     ) -> Result<(), Error> {
+        self.write_descriptor(c, USER_DESCRIPTION_UUID, description.as_bytes())
+            .await
+    }
+
+    /// Writes the value of a single descriptor of the given characteristic
+    /// # Errors
+    /// Returns an error if no device is connected, the characteristic or descriptor is not
+    /// available or if the write operation fails
+    pub async fn write_descriptor(&self, c: Uuid, d: Uuid, data: &[u8]) -> Result<(), Error> {
         let dev = self.connected_dev.lock().await;
         let dev = dev.as_ref().ok_or(Error::NoDeviceConnected)?;
         let state = self.state.lock().await;
-        let charac = state.get_charac(c)?;
-        dev.subscribe(charac).await?;
-        self.notify_listeners.lock().await.push(Listener {
-            uuid: charac.uuid,
-            callback: callback.into(),
-        });
-        Ok(())
+        let descriptor = state.get_descriptor(c, d)?;
+        self.cancel_on_disconnect(dev.write_descriptor(descriptor, data))
+            .await
     }
 
-    /// Unsubscribe from notifications for the given characteristic
-    /// This will also remove the callback from the list of listeners
+    /// Returns the negotiated link layer payload size (Data Length Extension) of the current
+    /// connection
+    /// None of the supported platforms expose the data length to apps. Android only reports
+    /// PHY and MTU changes and the desktop stacks negotiate it internally, so this currently
+    /// always fails with [`Error::NotSupported`] once a device is connected. Use
+    /// [`Handler::link_info`] for the values that are available.
     /// # Errors
-    /// Returns an error if no device is connected or the characteristic is not available
-    /// or if the unsubscribe operation fails
-    pub async fn unsubscribe(&self, c: Uuid) -> Result<(), Error> {
+    /// Returns an error if no device is connected or [`Error::NotSupported`]
+    pub async fn data_length(&self) -> Result<u16, Error> {
+        let dev = self.connected_dev.lock().await;
+        dev.as_ref().ok_or(Error::NoDeviceConnected)?;
+        Err(Error::NotSupported("data_length".to_string()))
+    }
+
+    /// Returns MTU, PHY, connection interval and RSSI of the current connection in one call
+    /// Values the platform can't provide are `None`, see [`LinkInfo`]
+    /// # Errors
+    /// Returns an error if no device is connected or querying the device fails
+    pub async fn link_info(&self) -> Result<LinkInfo, Error> {
+        let mtu = self.mtu().await;
         let dev = self.connected_dev.lock().await;
         let dev = dev.as_ref().ok_or(Error::NoDeviceConnected)?;
-        let state = self.state.lock().await;
-        let charac = state.get_charac(c)?;
-        dev.unsubscribe(charac).await?;
-        let mut listeners = self.notify_listeners.lock().await;
-        listeners.retain(|l| l.uuid != charac.uuid);
-        Ok(())
+        #[cfg(target_os = "android")]
+        {
+            use crate::android::LinkExt;
+            let link = dev.read_link().await?;
+            Ok(LinkInfo {
+                mtu,
+                tx_phy: link.tx_phy,
+                rx_phy: link.rx_phy,
+                connection_interval_ms: None,
+                rssi: link.rssi,
+            })
+        }
+        #[cfg(not(target_os = "android"))]
+        {
+            let rssi = dev.properties().await?.and_then(|p| p.rssi);
+            Ok(LinkInfo {
+                mtu,
+                rssi,
+                ..Default::default()
+            })
+        }
     }
 
     /// Request to change the MTU size for the currently connected peripheral
@@ -666,18 +3701,17 @@ impl Handler {
         let dev = self.connected_dev.lock().await;
         let dev = dev.as_ref().ok_or(Error::NoDeviceConnected)?;
 
-        // Get the device address
-        let addr = fmt_addr(dev.address());
-
         // Get the Android peripheral from our devices HashMap
         // Use a let binding to avoid the temporary value being dropped while borrowed
         let devices_lock = self.devices.lock().await;
         let android_dev = devices_lock
-            .get(&addr)
-            .ok_or(Error::UnknownPeripheral(addr.clone()))?;
+            .values()
+            .find(|p| p.id() == dev.id())
+            .ok_or(Error::UnknownPeripheral(fmt_addr(dev.address())))?;
 
         // Call the extension trait method
         let agreed_mtu = android_dev.request_mtu(mtu).await?;
+        self.state.lock().await.mtu = Some(agreed_mtu);
         Ok(agreed_mtu)
     }
 
@@ -691,7 +3725,7 @@ impl Handler {
     pub(super) async fn get_event_stream(
         &self,
     ) -> Result<Pin<Box<dyn Stream<Item = CentralEvent> + Send>>, Error> {
-        let events = self.adapter.events().await?;
+        let events = self.adapter().events().await?;
         Ok(events)
     }
 
@@ -703,22 +3737,139 @@ impl Handler {
             CentralEvent::DeviceConnected(peripheral_id) => {
                 self.handle_connect(peripheral_id).await;
             }
+            CentralEvent::StateUpdate(state) => {
+                self.send_status_event(BleStatusEvent::AdapterStateChanged(state.into()));
+            }
+            CentralEvent::DeviceDiscovered(peripheral_id)
+            | CentralEvent::DeviceUpdated(peripheral_id) => {
+                // nobody listening only means no per-device scan is running
+                let _ = self.discovered_tx.send(peripheral_id);
+            }
 
             _event => {}
         }
         Ok(())
     }
 
+    /// Gives scoped access to the btleplug [`Peripheral`] of the connected device, as an escape
+    /// hatch for capabilities the handler doesn't expose
+    /// The device stays locked while `f` runs, so no other operation of the handler can run in
+    /// between. Not available on Android, where the peripheral is implemented by the plugin.
+    /// # Errors
+    /// Returns an error if no device is connected
+    /// # Example
+    /// ```no_run
+    /// use btleplug::api::Peripheral as _;
+    ///
+    /// let handler = tauri_plugin_blec::get_handler().unwrap();
+    /// let characteristics = tauri::async_runtime::block_on(
+    ///     handler.with_connected_peripheral(|p| p.characteristics()),
+    /// )
+    /// .unwrap();
+    /// ```
+    #[cfg(not(target_os = "android"))]
+    pub async fn with_connected_peripheral<F, R>(&self, f: F) -> Result<R, Error>
+    where
+        F: FnOnce(&Peripheral) -> R,
+    {
+        let dev = self.connected_dev.lock().await;
+        let dev = dev.as_ref().ok_or(Error::NoDeviceConnected)?;
+        Ok(f(dev))
+    }
+
     /// Returns the connected device
     /// # Errors
     /// Returns an error if no device is connected
     pub async fn connected_device(&self) -> Result<BleDevice, Error> {
-        let p = self.connected_dev.lock().await;
-        let p = p.as_ref().ok_or(Error::NoDeviceConnected)?;
-        let d = BleDevice::from_peripheral(p).await?;
+        let p = self
+            .connected_dev
+            .lock()
+            .await
+            .clone()
+            .ok_or(Error::NoDeviceConnected)?;
+        let mut d = BleDevice::from_peripheral(&p, self.device_key().await).await?;
+        if let Some(known) = known_key(&*self.devices.lock().await, &p) {
+            d.address = known;
+        }
+        d.tag = self.state.lock().await.tags.get(&d.address).cloned();
         Ok(d)
     }
 
+    /// Returns the current information of a device found by a previous scan, e.g. to show the
+    /// latest RSSI in a detail view without handling the scan updates
+    /// Returns `None` if no scan found a device with the given address
+    pub async fn device(&self, address: &str) -> Option<BleDevice> {
+        let p = self.devices.lock().await.get(address)?.clone();
+        match BleDevice::from_peripheral(&p, self.device_key().await).await {
+            Ok(mut device) => {
+                device.address = address.to_string();
+                device.tag = self.state.lock().await.tags.get(address).cloned();
+                Some(device)
+            }
+            Err(e) => {
+                warn!("Failed to get device {address}: {e}");
+                None
+            }
+        }
+    }
+
+    /// Sends a device reported by a discovery event of the adapter to the per-device channel of
+    /// a scan, if it passes the scan filter and was not sent yet
+    #[allow(clippy::too_many_arguments)]
+    async fn forward_discovered(
+        &self,
+        adapter: &Adapter,
+        id: &PeripheralId,
+        filter: &ScanFilter,
+        key: DeviceKey,
+        self_devices: &Mutex<HashMap<String, Peripheral>>,
+        sent: &mut HashSet<String>,
+        device_tx: &mpsc::Sender<BleDevice>,
+    ) {
+        let Ok(p) = adapter.peripheral(id).await else {
+            return;
+        };
+        if known_key(&*self_devices.lock().await, &p).is_some_and(|key| sent.contains(&key)) {
+            return;
+        }
+        let mut matching = vec![p];
+        filter_peripherals(&mut matching, filter).await;
+        let Some(p) = matching.pop() else {
+            return;
+        };
+        let mut device = match BleDevice::from_peripheral(&p, key).await {
+            Ok(device) => device,
+            Err(e) => {
+                warn!("Failed to add device: {e}");
+                return;
+            }
+        };
+        {
+            let mut known = self_devices.lock().await;
+            match known_key(&known, &p) {
+                Some(key) => device.address = key,
+                None => {
+                    known.insert(device.address.clone(), p);
+                }
+            }
+        }
+        if sent.insert(device.address.clone()) {
+            self.apply_tags(std::slice::from_mut(&mut device)).await;
+            // a closed receiver only means nobody is interested anymore
+            let _ = device_tx.send(device).await;
+        }
+    }
+
+    async fn apply_tags(&self, devices: &mut [BleDevice]) {
+        let state = self.state.lock().await;
+        if state.tags.is_empty() {
+            return;
+        }
+        for d in devices {
+            d.tag = state.tags.get(&d.address).cloned();
+        }
+    }
+
     #[allow(clippy::redundant_closure_for_method_calls)]
     async fn handle_connect(&self, peripheral_id: PeripheralId) {
         let connected_device = self.connected_dev.lock().await.as_ref().map(|d| d.id());
@@ -726,7 +3877,7 @@ impl Handler {
             if connected_device == peripheral_id {
                 debug!("connection to {peripheral_id} established");
                 self.connected_tx
-                    .send(true)
+                    .send(Some(peripheral_id))
                     .expect("failed to send connected update");
             } else {
                 // event not for currently connected device, ignore
@@ -748,9 +3899,88 @@ impl Handler {
                 remove.push(i);
             }
         }
+        for i in remove.into_iter().rev() {
+            tx.swap_remove(i);
+        }
+    }
+
+    async fn set_connection_status(&self, status: ConnectionStatus) {
+        let mut state = self.state.lock().await;
+        if state.connection_status == status {
+            return;
+        }
+        state.connection_status = status;
+        let mut remove = vec![];
+        for (i, t) in state.connection_status_channel.iter_mut().enumerate() {
+            if let Err(e) = t.send(status).await {
+                warn!("Failed to send connection status: {e}");
+                remove.push(i);
+            }
+        }
+        for i in remove.into_iter().rev() {
+            state.connection_status_channel.swap_remove(i);
+        }
+    }
+
+    #[cfg_attr(not(target_os = "android"), allow(dead_code))]
+    pub(crate) async fn send_write_error(&self, error: models::WriteError) {
+        let tx = &mut self.state.lock().await.write_error_channel;
+        let mut remove = vec![];
+        for (i, t) in tx.iter_mut().enumerate() {
+            if let Err(e) = t.send(error.clone()).await {
+                warn!("Failed to send write error: {e}");
+                remove.push(i);
+            }
+        }
+        for i in remove.into_iter().rev() {
+            tx.swap_remove(i);
+        }
+    }
+
+    async fn send_any_disconnect(&self, peripheral_id: &PeripheralId) {
+        let address = self
+            .devices
+            .lock()
+            .await
+            .iter()
+            .find(|(_, p)| p.id() == *peripheral_id)
+            .map_or_else(|| peripheral_id.to_string(), |(key, _)| key.clone());
+        let tx = &mut self.state.lock().await.any_disconnect_channel;
+        let mut remove = vec![];
+        for (i, t) in tx.iter_mut().enumerate() {
+            if let Err(e) = t.send(address.clone()).await {
+                warn!("Failed to send disconnect of {address}: {e}");
+                remove.push(i);
+            }
+        }
+        for i in remove.into_iter().rev() {
+            tx.swap_remove(i);
+        }
+    }
+
+    async fn send_scan_complete(&self, count: usize) {
+        let tx = &mut self.state.lock().await.scan_complete_channel;
+        let mut remove = vec![];
+        for (i, t) in tx.iter_mut().enumerate() {
+            if let Err(e) = t.send(count).await {
+                warn!("Failed to send scan complete: {e}");
+                remove.push(i);
+            }
+        }
+        for i in remove.into_iter().rev() {
+            tx.swap_remove(i);
+        }
     }
 
     async fn send_scan_update(&self, state: bool) {
+        if !state {
+            self.scanning.store(0, Ordering::Release);
+        }
+        self.send_status_event(if state {
+            BleStatusEvent::ScanStarted
+        } else {
+            BleStatusEvent::ScanStopped
+        });
         let tx = &mut self.state.lock().await.scan_update_channel;
         let mut remove = vec![];
         for (i, t) in tx.iter_mut().enumerate() {
@@ -759,7 +3989,40 @@ impl Handler {
                 remove.push(i);
             }
         }
+        for i in remove.into_iter().rev() {
+            tx.swap_remove(i);
+        }
+    }
+}
+
+/// Checks that the characteristic accepts writes of the given type
+/// Empty properties mean the platform did not report them, the device decides then
+fn check_write_type(charac: &Characteristic, write_type: models::WriteType) -> Result<(), Error> {
+    if charac.properties.is_empty() {
+        return Ok(());
+    }
+    let supported = models::WriteType::supported(charac.properties);
+    if supported.is_empty() {
+        return Err(Error::NotWritable(charac.uuid.to_string()));
     }
+    if !supported.contains(&write_type) {
+        return Err(Error::WriteTypeNotSupported(
+            charac.uuid.to_string(),
+            format!("{write_type:?}"),
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the key a peripheral was added to the device map with
+/// A device keeps its key for the lifetime of the entry, so e.g. a name that only arrives with
+/// a later scan response doesn't change it
+fn known_key(devices: &HashMap<String, Peripheral>, p: &Peripheral) -> Option<String> {
+    let id = p.id();
+    devices
+        .iter()
+        .find(|(_, d)| d.id() == id)
+        .map(|(key, _)| key.clone())
 }
 
 async fn filter_peripherals(discovered: &mut Vec<Peripheral>, filter: &ScanFilter) {
@@ -826,16 +4089,225 @@ async fn filter_peripherals(discovered: &mut Vec<Peripheral>, filter: &ScanFilte
     }
 }
 
-async fn listen_notify(dev: Option<Peripheral>, listeners: Arc<Mutex<Vec<Listener>>>) {
+/// Returns whether a failed operation may succeed when tried again
+fn is_transient(e: &btleplug::Error) -> bool {
+    !matches!(
+        e,
+        btleplug::Error::NotConnected
+            | btleplug::Error::PermissionDenied
+            | btleplug::Error::NotSupported(_)
+            | btleplug::Error::DeviceNotFound
+    )
+}
+
+/// Connects to the device, discovers its services and disconnects again
+/// Returns `false` if any step fails or takes longer than [`CHARACTERISTIC_CHECK_TIMEOUT`]
+async fn has_characteristic(
+    p: &Peripheral,
+    charac: Uuid,
+    connected: Option<&PeripheralId>,
+) -> bool {
+    let found = with_temporary_connection(
+        p,
+        connected,
+        CHARACTERISTIC_CHECK_TIMEOUT,
+        "checking the characteristics",
+        || async {
+            if p.services().is_empty() {
+                p.discover_services().await?;
+            }
+            Ok(p.characteristics().iter().any(|c| c.uuid == charac))
+        },
+    )
+    .await;
+    found.unwrap_or(false)
+}
+
+/// Connects to the device, discovers its services and disconnects again
+/// Services that were already discovered are returned without connecting.
+/// Returns `None` if any step fails or takes longer than [`SERVICE_DISCOVERY_TIMEOUT`]
+async fn discover_gatt_services(
+    p: &Peripheral,
+    connected: Option<&PeripheralId>,
+) -> Option<Vec<Service>> {
+    if p.services().is_empty() {
+        with_temporary_connection(
+            p,
+            connected,
+            SERVICE_DISCOVERY_TIMEOUT,
+            "discovering the services",
+            || p.discover_services(),
+        )
+        .await?;
+    }
+    Some(p.services().iter().map(Service::from).collect())
+}
+
+/// Connects to a device found by a scan, runs `f` and disconnects again, to get details that
+/// are not advertised
+/// A device that is connected already, or the device tracked by the handler, is used as is
+/// and stays connected, the handler manages the connection of its device.
+/// Returns `None` if connecting or `f` fails or both take longer than `timeout`
+async fn with_temporary_connection<T, F, Fut>(
+    p: &Peripheral,
+    connected: Option<&PeripheralId>,
+    timeout: Duration,
+    action: &str,
+    f: F,
+) -> Option<T>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T, btleplug::Error>>,
+{
+    let was_connected = connected == Some(&p.id()) || p.is_connected().await.unwrap_or(false);
+    let result = tokio::time::timeout(timeout, async {
+        if !was_connected {
+            p.connect().await?;
+        }
+        f().await
+    })
+    .await;
+    if !was_connected {
+        if let Err(e) = p.disconnect().await {
+            warn!("Failed to disconnect after {action}: {e}");
+        }
+    }
+    match result {
+        Ok(Ok(value)) => Some(value),
+        Ok(Err(e)) => {
+            debug!("Failed {action} of {}: {e}", p.id());
+            None
+        }
+        Err(_) => {
+            debug!("Timed out {action} of {}", p.id());
+            None
+        }
+    }
+}
+
+/// Resolves the names of all nameless devices that were not tried yet, with at most
+/// [`MAX_TEMPORARY_CONNECTIONS`] connections at a time
+/// The connected device is skipped, it must not be disconnected by the scan
+async fn resolve_names(
+    discovered: &[Peripheral],
+    names: &mut HashMap<PeripheralId, Option<String>>,
+    connected: Option<&PeripheralId>,
+) {
+    let mut nameless = vec![];
+    for p in discovered {
+        let id = p.id();
+        if names.contains_key(&id) || connected == Some(&id) {
+            continue;
+        }
+        let props = p.properties().await.ok().flatten();
+        if props.map_or(true, |props| props.local_name.is_none()) {
+            nameless.push(p);
+        }
+    }
+    let lookups: Vec<_> = nameless.iter().map(|p| resolve_name(p)).collect();
+    let resolved: Vec<_> = futures::stream::iter(lookups)
+        .buffered(MAX_TEMPORARY_CONNECTIONS)
+        .collect()
+        .await;
+    for (p, name) in nameless.into_iter().zip(resolved) {
+        names.insert(p.id(), name);
+    }
+}
+
+/// Connects to the device, reads the GAP Device Name characteristic and disconnects again
+/// Returns `None` if any step fails or takes longer than [`NAME_RESOLVE_TIMEOUT`]
+async fn resolve_name(p: &Peripheral) -> Option<String> {
+    let name = with_temporary_connection(
+        p,
+        None,
+        NAME_RESOLVE_TIMEOUT,
+        "resolving the name",
+        || async {
+            if p.services().is_empty() {
+                p.discover_services().await?;
+            }
+            let charac = p
+                .characteristics()
+                .into_iter()
+                .find(|c| c.uuid == DEVICE_NAME_UUID);
+            match charac {
+                Some(c) => Ok(Some(p.read(&c).await?)),
+                None => Ok(None),
+            }
+        },
+    )
+    .await;
+    name.flatten().and_then(|n| String::from_utf8(n).ok())
+}
+
+/// Returns pseudo random bytes, good enough for test payloads
+fn random_bytes(size: usize) -> Vec<u8> {
+    use std::hash::{BuildHasher, Hasher};
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    let mut data = Vec::with_capacity(size);
+    while data.len() < size {
+        hasher.write_usize(data.len());
+        data.extend(hasher.finish().to_le_bytes());
+    }
+    data.truncate(size);
+    data
+}
+
+/// Returns the services of the device
+/// On Android getting the services needs a plugin call that can fail, e.g. if the discovery
+/// has not finished yet. It is retried a few times and returns an error instead of panicking.
+async fn device_services(
+    dev: &Peripheral,
+) -> Result<std::collections::BTreeSet<btleplug::api::Service>, Error> {
+    #[cfg(target_os = "android")]
+    {
+        use crate::android::ServicesExt;
+        Ok(dev.fetch_services().await?)
+    }
+    #[cfg(not(target_os = "android"))]
+    Ok(dev.services())
+}
+
+async fn listen_notify(
+    dev: Option<Peripheral>,
+    listeners: Arc<Mutex<Vec<Listener>>>,
+    status_tx: broadcast::Sender<BleStatusEvent>,
+) {
+    let dev = dev.expect("no device connected");
     let mut stream = dev
-        .expect("no device connected")
         .notifications()
         .await
         .expect("failed to get notifications stream");
     let mut handles: HashMap<Uuid, tokio::task::JoinHandle<()>> = HashMap::new();
     while let Some(data) = stream.next().await {
-        for l in listeners.lock().await.iter() {
-            if l.uuid == data.uuid {
+        for l in listeners.lock().await.iter_mut() {
+            if l.uuid != data.uuid {
+                continue;
+            }
+            l.last_notification_at = Some(Instant::now());
+            l.last_received = Some(data.value.clone());
+            let values = match &mut l.reassembler {
+                Some(reassembler) => reassembler.push(&data.value),
+                None => vec![data.value.clone()],
+            };
+            for value in values {
+                if l.filter.as_ref().is_some_and(|f| !f(&value)) {
+                    continue;
+                }
+                if l.only_changed {
+                    if l.last_delivered.as_ref() == Some(&value) {
+                        continue;
+                    }
+                    l.last_delivered = Some(value.clone());
+                }
+                if l.paused {
+                    l.last_value = Some(value);
+                    continue;
+                }
+                if let Some(throttle) = &l.throttle {
+                    throttle.push(value);
+                    continue;
+                }
                 let cb = l.callback.clone();
                 // wait for running callback first
                 if let Some(handle) = handles.remove(&l.uuid) {
@@ -844,8 +4316,183 @@ async fn listen_notify(dev: Option<Peripheral>, listeners: Arc<Mutex<Vec<Listene
                 }
                 // insert new callback
                 trace!("starting new callback for {:?}", l.uuid);
-                handles.insert(l.uuid, tokio::task::spawn(cb.run(data.value.clone())));
+                handles.insert(l.uuid, tokio::task::spawn(cb.run(value)));
             }
         }
     }
+    // on disconnect this task is aborted, so the stream ending on its own while the device is
+    // still connected means notifications silently stopped
+    if dev.is_connected().await.unwrap_or(false) {
+        warn!("notification stream ended while device is still connected");
+        let _ = status_tx.send(BleStatusEvent::NotificationsStopped);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRIMARY: Uuid = uuid_from_u16(0x180D);
+    const INCLUDED: Uuid = uuid_from_u16(0x180F);
+    const SHARED: Uuid = uuid_from_u16(0x2A19);
+
+    fn state_with_shared_characteristic() -> HandlerState {
+        let mut state = HandlerState::new();
+        // the included service is listed first, like some platforms report it
+        for (service, primary) in [(INCLUDED, false), (PRIMARY, true)] {
+            state.characs.push(Characteristic {
+                uuid: SHARED,
+                service_uuid: service,
+                properties: CharPropFlags::READ,
+                descriptors: std::collections::BTreeSet::new(),
+            });
+            state.services.push(Service {
+                uuid: service,
+                name: None,
+                primary,
+                characteristics: vec![],
+            });
+        }
+        state
+    }
+
+    #[test]
+    fn find_charac_prefers_primary_service() {
+        let state = state_with_shared_characteristic();
+        let charac = state.get_charac(SHARED).unwrap();
+        assert_eq!(charac.service_uuid, PRIMARY);
+    }
+
+    #[test]
+    fn find_charac_in_given_service() {
+        let state = state_with_shared_characteristic();
+        let charac = state.find_charac(Some(INCLUDED), SHARED).unwrap();
+        assert_eq!(charac.service_uuid, INCLUDED);
+        assert!(matches!(
+            state.find_charac(Some(uuid_from_u16(0x1800)), SHARED),
+            Err(Error::CharacNotAvailable(_))
+        ));
+    }
+
+    fn device(address: &str, rssi: i16) -> BleDevice {
+        BleDevice {
+            address: address.to_string(),
+            name: address.to_string(),
+            is_connected: false,
+            manufacturer_data: HashMap::new(),
+            service_data: HashMap::new(),
+            services: vec![],
+            rssi: Some(rssi),
+            raw_advertisement: None,
+            is_paired: None,
+            tag: None,
+            gatt_services: None,
+        }
+    }
+
+    fn rssis(devices: &[BleDevice]) -> Vec<(&str, Option<i16>)> {
+        devices
+            .iter()
+            .map(|d| (d.address.as_str(), d.rssi))
+            .collect()
+    }
+
+    #[test]
+    fn scan_updates_send_all_devices_by_default() {
+        let mut updates = ScanUpdates::new(ScanOptions::default());
+        let devices = [device("a", -40), device("b", -50)];
+        assert_eq!(updates.next(&devices, false).unwrap().len(), 2);
+        assert_eq!(updates.next(&devices, false).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn scan_updates_only_send_changed_devices() {
+        let mut updates = ScanUpdates::new(ScanOptions {
+            only_changed: true,
+            ..Default::default()
+        });
+        let devices = [device("a", -40), device("b", -50)];
+        assert_eq!(updates.next(&devices, false).unwrap().len(), 2);
+        assert!(updates.next(&devices, false).unwrap().is_empty());
+        // a changed device is sent with its new values, new devices are added
+        let devices = [device("a", -40), device("b", -60), device("c", -70)];
+        assert_eq!(
+            rssis(&updates.next(&devices, false).unwrap()),
+            vec![("b", Some(-60)), ("c", Some(-70))]
+        );
+        assert!(updates.next(&devices, false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn scan_updates_throttle_to_the_interval() {
+        let mut updates = ScanUpdates::new(ScanOptions {
+            update_interval: Some(60_000),
+            only_changed: true,
+            ..Default::default()
+        });
+        assert_eq!(updates.next(&[device("a", -40)], false).unwrap().len(), 1);
+        // changes within the interval are held back and merged into the next update
+        assert!(updates.next(&[device("a", -45)], false).is_none());
+        let devices = [device("a", -50), device("b", -60)];
+        assert!(updates.next(&devices, false).is_none());
+        assert_eq!(
+            rssis(&updates.next(&devices, true).unwrap()),
+            vec![("a", Some(-50)), ("b", Some(-60))]
+        );
+    }
+
+    const LENGTH_PREFIXED: Framing = Framing::LengthPrefixed {
+        header_len: 3,
+        length_offset: 1,
+        length_size: 2,
+    };
+
+    #[test]
+    fn reassembles_delimited_messages() {
+        let mut reassembler = Reassembler::new(Framing::Delimited(b'\n'));
+        assert_eq!(
+            reassembler.push(b"ab\ncd\n"),
+            vec![b"ab".to_vec(), b"cd".to_vec()]
+        );
+        assert!(reassembler.push(b"ef").is_empty());
+        assert_eq!(reassembler.push(b"g\n"), vec![b"efg".to_vec()]);
+    }
+
+    #[test]
+    fn reassembles_length_prefixed_messages() {
+        let mut reassembler = Reassembler::new(LENGTH_PREFIXED);
+        assert_eq!(
+            reassembler.push(&[0xAA, 0x02, 0x00, 0x01, 0x02, 0xBB, 0x00, 0x00]),
+            vec![vec![0xAA, 0x02, 0x00, 0x01, 0x02], vec![0xBB, 0x00, 0x00]]
+        );
+    }
+
+    #[test]
+    fn reassembles_messages_split_across_notifications() {
+        let mut reassembler = Reassembler::new(LENGTH_PREFIXED);
+        assert!(reassembler.push(&[0xAA, 0x03]).is_empty());
+        assert!(reassembler.push(&[0x00, 0x01, 0x02]).is_empty());
+        assert_eq!(
+            reassembler.push(&[0x03, 0xBB]),
+            vec![vec![0xAA, 0x03, 0x00, 0x01, 0x02, 0x03]]
+        );
+        assert_eq!(reassembler.buffer, vec![0xBB]);
+    }
+
+    #[test]
+    fn drops_buffer_above_the_maximum() {
+        let mut reassembler = Reassembler::new(Framing::LengthPrefixed {
+            header_len: 5,
+            length_offset: 1,
+            length_size: 4,
+        });
+        // announces a message larger than the buffer, so it never completes
+        assert!(reassembler.push(&[0xAA, 0xFF, 0xFF, 0xFF, 0xFF]).is_empty());
+        assert!(reassembler.push(&vec![0; MAX_REASSEMBLY_BUFFER]).is_empty());
+        assert!(reassembler.buffer.is_empty());
+        assert_eq!(
+            reassembler.push(&[0xAA, 0x01, 0x00, 0x00, 0x00, 0x05]),
+            vec![vec![0xAA, 0x01, 0x00, 0x00, 0x00, 0x05]]
+        );
+    }
 }
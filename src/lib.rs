@@ -3,7 +3,7 @@ use once_cell::sync::OnceCell;
 use tauri::{
     async_runtime,
     plugin::{Builder, TauriPlugin},
-    Wry,
+    Manager, Wry,
 };
 
 #[cfg(target_os = "android")]
@@ -12,10 +12,12 @@ mod commands;
 mod error;
 mod handler;
 pub mod models;
+#[cfg(all(feature = "peripheral", target_os = "linux"))]
+pub mod peripheral;
 
 pub use error::Error;
 pub use handler::Handler;
-pub use handler::{OnDisconnectHandler, SubscriptionHandler};
+pub use handler::{OnDisconnectHandler, ScanHandle, SubscriptionHandler};
 
 static HANDLER: OnceCell<Handler> = OnceCell::new();
 
@@ -23,15 +25,52 @@ static HANDLER: OnceCell<Handler> = OnceCell::new();
 /// # Panics
 /// Panics if the handler cannot be initialized.
 pub fn init() -> TauriPlugin<Wry> {
-    let handler = async_runtime::block_on(Handler::new()).expect("failed to initialize handler");
+    try_init().expect("failed to initialize handler")
+}
+
+/// Initializes the plugin, returning an error instead of panicking if the handler can't be
+/// created, e.g. because the device has no Bluetooth adapter.
+/// Use [`init_unavailable`] in that case to keep the app running without BLE.
+/// # Errors
+/// Returns an error if the handler cannot be initialized.
+/// # Example
+/// ```no_run
+/// let blec = tauri_plugin_blec::try_init().unwrap_or_else(|e| {
+///     eprintln!("Bluetooth unavailable: {e}");
+///     tauri_plugin_blec::init_unavailable()
+/// });
+/// tauri::Builder::default().plugin(blec);
+/// ```
+pub fn try_init() -> Result<TauriPlugin<Wry>, Error> {
+    let handler = async_runtime::block_on(Handler::new())?;
     let _ = HANDLER.set(handler);
+    Ok(plugin())
+}
+
+/// Initializes the plugin without a handler.
+/// All commands return [`Error::HandlerNotInitialized`], so the frontend can show that BLE is
+/// unavailable instead of the app failing to start.
+pub fn init_unavailable() -> TauriPlugin<Wry> {
+    plugin()
+}
 
+fn plugin() -> TauriPlugin<Wry> {
     #[allow(unused)]
     Builder::new("blec")
         .invoke_handler(commands::commands())
         .setup(|app, api| {
+            let Ok(handler) = get_handler() else {
+                tracing::warn!("blec handler not initialized, BLE is unavailable");
+                return Ok(());
+            };
             #[cfg(target_os = "android")]
-            android::init(app, api)?;
+            {
+                android::init(app, api)?;
+                async_runtime::spawn(handle_write_errors());
+            }
+            if let Ok(dir) = app.path().app_data_dir() {
+                handler.set_data_dir(dir);
+            }
             async_runtime::spawn(handle_events());
             Ok(())
         })
@@ -66,18 +105,42 @@ pub async fn request_mtu(mtu: u16) -> Result<u16, Error> {
     handler.request_mtu(mtu).await
 }
 
+#[cfg(target_os = "android")]
+async fn handle_write_errors() {
+    let handler = get_handler().expect("failed to get handler");
+    let mut errors = android::write_errors().expect("failed to get write errors");
+    while let Some(error) = errors.next().await {
+        tracing::warn!(
+            "write to {} failed: {}",
+            error.characteristic,
+            error.message
+        );
+        handler.send_write_error(error).await;
+    }
+}
+
 async fn handle_events() {
     let handler = get_handler().expect("failed to get handler");
-    let stream = handler
-        .get_event_stream()
-        .await
-        .expect("failed to get event stream");
-    stream
-        .for_each(|event| async {
-            handler
-                .handle_event(event)
-                .await
-                .expect("failed to handle event");
-        })
-        .await;
+    let mut adapter_changes = handler.adapter_changes();
+    loop {
+        let generation = *adapter_changes.borrow_and_update();
+        let stream = handler
+            .get_event_stream()
+            .await
+            .expect("failed to get event stream");
+        handler.set_ready();
+        stream
+            .take_until(adapter_changes.changed())
+            .for_each(|event| async {
+                handler
+                    .handle_event(event)
+                    .await
+                    .expect("failed to handle event");
+            })
+            .await;
+        if *adapter_changes.borrow() == generation {
+            // the stream ended without the adapter being replaced
+            break;
+        }
+    }
 }
@@ -1,12 +1,20 @@
 use std::collections::HashMap;
 
-use btleplug::api::BDAddr;
+use btleplug::api::{
+    bleuuid::{uuid_from_u16, uuid_from_u32, BleUuid},
+    BDAddr, Peripheral as _,
+};
 use enumflags2::BitFlags;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::error;
 
+#[cfg(target_os = "android")]
+use crate::android::Peripheral;
+#[cfg(not(target_os = "android"))]
+use btleplug::platform::Peripheral;
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BleDevice {
@@ -17,6 +25,20 @@ pub struct BleDevice {
     pub service_data: HashMap<Uuid, Vec<u8>>,
     pub services: Vec<Uuid>,
     pub rssi: Option<i16>,
+    /// Raw advertisement payload including all AD structures.
+    /// This is best effort and only available on Android at the moment.
+    /// It is always `None` on Apple platforms, because CoreBluetooth does not expose the raw data.
+    pub raw_advertisement: Option<Vec<u8>>,
+    /// Whether the device is bonded with this system.
+    /// Only available on Android, `None` on platforms where the bond state can't be queried.
+    pub is_paired: Option<bool>,
+    /// Tag given to the device with [`ConnectOptions::tag`], kept across reconnects
+    #[serde(default)]
+    pub tag: Option<String>,
+    /// GATT services with their characteristics, only set for devices matching a service
+    /// filter of a scan with [`ScanOptions::discover_filtered_services`]
+    #[serde(default)]
+    pub gatt_services: Option<Vec<Service>>,
 }
 
 impl Eq for BleDevice {}
@@ -39,15 +61,49 @@ impl PartialEq for BleDevice {
     }
 }
 
+/// Key that identifies discovered devices, used as [`BleDevice::address`] and to look devices
+/// up when connecting
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DeviceKey {
+    /// MAC address of the device. Apple platforms hide the address, the platform id is used
+    /// there instead
+    #[default]
+    Address,
+    /// Platform id of the peripheral, e.g. the UUID CoreBluetooth assigns to the device
+    Id,
+    /// Advertised local name, falling back to the address for devices without a name.
+    /// Only use this if the names of the devices are unique. A device whose name only arrives
+    /// with a later scan response keeps its address as key until the next scan
+    NameThenAddress,
+}
+
+impl DeviceKey {
+    fn key(self, peripheral: &Peripheral, local_name: Option<&str>) -> String {
+        match self {
+            Self::Address => platform_address(peripheral),
+            Self::Id => peripheral.id().to_string(),
+            Self::NameThenAddress => {
+                local_name.map_or_else(|| platform_address(peripheral), str::to_string)
+            }
+        }
+    }
+}
+
+fn platform_address(peripheral: &Peripheral) -> String {
+    #[cfg(target_vendor = "apple")]
+    return peripheral.id().to_string();
+    #[cfg(not(target_vendor = "apple"))]
+    return peripheral.address().to_string();
+}
+
 impl BleDevice {
-    pub(crate) async fn from_peripheral<P: btleplug::api::Peripheral>(
-        peripheral: &P,
+    pub(crate) async fn from_peripheral(
+        peripheral: &Peripheral,
+        key: DeviceKey,
     ) -> Result<Self, error::Error> {
-        #[cfg(target_vendor = "apple")]
-        let address = peripheral.id().to_string();
-        #[cfg(not(target_vendor = "apple"))]
-        let address = peripheral.address().to_string();
         let properties = peripheral.properties().await?.unwrap_or_default();
+        let address = key.key(peripheral, properties.local_name.as_deref());
         let name = properties
             .local_name
             .unwrap_or_else(|| peripheral.id().to_string());
@@ -58,6 +114,16 @@ impl BleDevice {
             service_data: properties.service_data,
             services: properties.services,
             rssi: properties.rssi,
+            #[cfg(target_os = "android")]
+            raw_advertisement: peripheral.raw_advertisement(),
+            #[cfg(not(target_os = "android"))]
+            raw_advertisement: None,
+            #[cfg(target_os = "android")]
+            is_paired: Some(peripheral.is_paired()),
+            #[cfg(not(target_os = "android"))]
+            is_paired: None,
+            tag: None,
+            gatt_services: None,
             is_connected: peripheral.is_connected().await?,
         })
     }
@@ -66,13 +132,27 @@ impl BleDevice {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Service {
     pub uuid: Uuid,
+    /// Standard name of the service if it is defined by the Bluetooth SIG, see [`well_known_name`]
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Whether this is a primary service
+    #[serde(default)]
+    pub primary: bool,
     pub characteristics: Vec<Characteristic>,
 }
 
+/// Complete GATT tree of the connected device
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct GattTree {
+    pub services: Vec<Service>,
+}
+
 impl From<&btleplug::api::Service> for Service {
     fn from(service: &btleplug::api::Service) -> Self {
         Self {
             uuid: service.uuid,
+            name: well_known_name(service.uuid).map(str::to_string),
+            primary: service.primary,
             characteristics: service
                 .characteristics
                 .iter()
@@ -85,6 +165,9 @@ impl From<&btleplug::api::Service> for Service {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Characteristic {
     pub uuid: Uuid,
+    /// Standard name of the characteristic if it is defined by the Bluetooth SIG, see [`well_known_name`]
+    #[serde(default)]
+    pub name: Option<String>,
     pub descriptors: Vec<Uuid>,
     pub properties: BitFlags<CharProps>,
 }
@@ -93,6 +176,7 @@ impl From<&btleplug::api::Characteristic> for Characteristic {
     fn from(characteristic: &btleplug::api::Characteristic) -> Self {
         Self {
             uuid: characteristic.uuid,
+            name: well_known_name(characteristic.uuid).map(str::to_string),
             descriptors: characteristic.descriptors.iter().map(|d| d.uuid).collect(),
             properties: get_flags(characteristic.properties),
         }
@@ -131,7 +215,7 @@ impl From<btleplug::api::CharPropFlags> for CharProps {
     }
 }
 
-fn get_flags(properties: btleplug::api::CharPropFlags) -> BitFlags<CharProps, u8> {
+pub(crate) fn get_flags(properties: btleplug::api::CharPropFlags) -> BitFlags<CharProps, u8> {
     let mut flags = BitFlags::empty();
     for flag in properties.iter() {
         flags |= CharProps::from(flag);
@@ -139,6 +223,196 @@ fn get_flags(properties: btleplug::api::CharPropFlags) -> BitFlags<CharProps, u8
     flags
 }
 
+/// Returns the human readable name of a service, characteristic or descriptor UUID assigned
+/// by the Bluetooth SIG, or `None` if the UUID is not a commonly used standard UUID
+/// # Example
+/// ```
+/// use btleplug::api::bleuuid::uuid_from_u16;
+/// use tauri_plugin_blec::models::well_known_name;
+/// assert_eq!(
+///     well_known_name(uuid_from_u16(0x2902)),
+///     Some("Client Characteristic Configuration")
+/// );
+/// ```
+#[must_use]
+pub fn well_known_name(uuid: Uuid) -> Option<&'static str> {
+    let name = match uuid.to_ble_u16()? {
+        // services
+        0x1800 => "Generic Access",
+        0x1801 => "Generic Attribute",
+        0x1802 => "Immediate Alert",
+        0x1803 => "Link Loss",
+        0x1804 => "Tx Power",
+        0x1805 => "Current Time",
+        0x1806 => "Reference Time Update",
+        0x1807 => "Next DST Change",
+        0x1808 => "Glucose",
+        0x1809 => "Health Thermometer",
+        0x180A => "Device Information",
+        0x180D => "Heart Rate",
+        0x180E => "Phone Alert Status",
+        0x180F => "Battery",
+        0x1810 => "Blood Pressure",
+        0x1811 => "Alert Notification",
+        0x1812 => "Human Interface Device",
+        0x1813 => "Scan Parameters",
+        0x1814 => "Running Speed and Cadence",
+        0x1815 => "Automation IO",
+        0x1816 => "Cycling Speed and Cadence",
+        0x1818 => "Cycling Power",
+        0x1819 => "Location and Navigation",
+        0x181A => "Environmental Sensing",
+        0x181B => "Body Composition",
+        0x181C => "User Data",
+        0x181D => "Weight Scale",
+        0x181E => "Bond Management",
+        0x181F => "Continuous Glucose Monitoring",
+        0x1822 => "Pulse Oximeter",
+        0x1826 => "Fitness Machine",
+        0x1827 => "Mesh Provisioning",
+        0x1828 => "Mesh Proxy",
+        // descriptors
+        0x2900 => "Characteristic Extended Properties",
+        0x2901 => "Characteristic User Description",
+        0x2902 => "Client Characteristic Configuration",
+        0x2903 => "Server Characteristic Configuration",
+        0x2904 => "Characteristic Presentation Format",
+        0x2905 => "Characteristic Aggregate Format",
+        0x2906 => "Valid Range",
+        0x2907 => "External Report Reference",
+        0x2908 => "Report Reference",
+        0x2909 => "Number of Digitals",
+        0x290A => "Value Trigger Setting",
+        0x290B => "Environmental Sensing Configuration",
+        0x290C => "Environmental Sensing Measurement",
+        0x290D => "Environmental Sensing Trigger Setting",
+        0x290E => "Time Trigger Setting",
+        // characteristics
+        0x2A00 => "Device Name",
+        0x2A01 => "Appearance",
+        0x2A02 => "Peripheral Privacy Flag",
+        0x2A03 => "Reconnection Address",
+        0x2A04 => "Peripheral Preferred Connection Parameters",
+        0x2A05 => "Service Changed",
+        0x2A06 => "Alert Level",
+        0x2A07 => "Tx Power Level",
+        0x2A08 => "Date Time",
+        0x2A19 => "Battery Level",
+        0x2A1C => "Temperature Measurement",
+        0x2A1D => "Temperature Type",
+        0x2A23 => "System ID",
+        0x2A24 => "Model Number String",
+        0x2A25 => "Serial Number String",
+        0x2A26 => "Firmware Revision String",
+        0x2A27 => "Hardware Revision String",
+        0x2A28 => "Software Revision String",
+        0x2A29 => "Manufacturer Name String",
+        0x2A2A => "IEEE 11073-20601 Regulatory Certification Data List",
+        0x2A2B => "Current Time",
+        0x2A35 => "Blood Pressure Measurement",
+        0x2A37 => "Heart Rate Measurement",
+        0x2A38 => "Body Sensor Location",
+        0x2A39 => "Heart Rate Control Point",
+        0x2A4A => "HID Information",
+        0x2A4B => "Report Map",
+        0x2A4C => "HID Control Point",
+        0x2A4D => "Report",
+        0x2A4E => "Protocol Mode",
+        0x2A50 => "PnP ID",
+        0x2A53 => "RSC Measurement",
+        0x2A5B => "CSC Measurement",
+        0x2A5C => "CSC Feature",
+        0x2A63 => "Cycling Power Measurement",
+        0x2A65 => "Cycling Power Feature",
+        0x2A6D => "Pressure",
+        0x2A6E => "Temperature",
+        0x2A6F => "Humidity",
+        0x2A9D => "Weight Measurement",
+        0x2A9E => "Weight Scale Feature",
+        0x2AA6 => "Central Address Resolution",
+        0x2AC9 => "Resolvable Private Address Only",
+        0x2B29 => "Client Supported Features",
+        0x2B2A => "Database Hash",
+        0x2B3A => "Server Supported Features",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// UUID of a service, characteristic or descriptor as accepted by the commands
+/// Besides the full 128-bit form, a 16-bit or 32-bit short UUID assigned by the Bluetooth SIG
+/// can be given as a number (`0x2A37`) or hex string (`"2A37"`, `"0x2A37"`), it is expanded
+/// with the Bluetooth base UUID so it matches the UUID reported by the device
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "RawGattUuid", into = "String")]
+pub struct GattUuid(pub Uuid);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawGattUuid {
+    Number(u32),
+    Text(String),
+}
+
+impl TryFrom<RawGattUuid> for GattUuid {
+    type Error = uuid::Error;
+
+    fn try_from(raw: RawGattUuid) -> Result<Self, Self::Error> {
+        match raw {
+            RawGattUuid::Number(short) => Ok(Self::from(short)),
+            RawGattUuid::Text(text) => text.parse(),
+        }
+    }
+}
+
+impl std::str::FromStr for GattUuid {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let short = s
+            .strip_prefix("0x")
+            .or_else(|| s.strip_prefix("0X"))
+            .unwrap_or(s);
+        if short.len() <= 8 {
+            if let Ok(short) = u32::from_str_radix(short, 16) {
+                return Ok(Self::from(short));
+            }
+        }
+        Uuid::parse_str(s).map(Self)
+    }
+}
+
+impl From<u16> for GattUuid {
+    fn from(short: u16) -> Self {
+        Self(uuid_from_u16(short))
+    }
+}
+
+impl From<u32> for GattUuid {
+    fn from(short: u32) -> Self {
+        Self(uuid_from_u32(short))
+    }
+}
+
+impl From<Uuid> for GattUuid {
+    fn from(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+}
+
+impl From<GattUuid> for Uuid {
+    fn from(uuid: GattUuid) -> Self {
+        uuid.0
+    }
+}
+
+impl From<GattUuid> for String {
+    fn from(uuid: GattUuid) -> Self {
+        uuid.0.to_string()
+    }
+}
+
 #[must_use]
 pub fn fmt_addr(addr: BDAddr) -> String {
     let a = addr.into_inner();
@@ -148,7 +422,7 @@ pub fn fmt_addr(addr: BDAddr) -> String {
     )
 }
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum WriteType {
     /// aka request.
@@ -157,6 +431,21 @@ pub enum WriteType {
     WithoutResponse,
 }
 
+impl WriteType {
+    /// Returns the write types allowed by the given characteristic properties
+    #[must_use]
+    pub fn supported(properties: btleplug::api::CharPropFlags) -> Vec<Self> {
+        let mut types = vec![];
+        if properties.contains(btleplug::api::CharPropFlags::WRITE) {
+            types.push(WriteType::WithResponse);
+        }
+        if properties.contains(btleplug::api::CharPropFlags::WRITE_WITHOUT_RESPONSE) {
+            types.push(WriteType::WithoutResponse);
+        }
+        types
+    }
+}
+
 impl From<WriteType> for btleplug::api::WriteType {
     fn from(write_type: WriteType) -> Self {
         match write_type {
@@ -166,9 +455,326 @@ impl From<WriteType> for btleplug::api::WriteType {
     }
 }
 
+/// Filter for notifications that is applied before the data is sent to the frontend
+/// Matches if the notification contains `value` starting at byte `offset`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotifyFilter {
+    pub offset: usize,
+    pub value: Vec<u8>,
+}
+
+impl NotifyFilter {
+    /// Returns true if the notification data passes the filter
+    #[must_use]
+    pub fn matches(&self, data: &[u8]) -> bool {
+        let Some(end) = self.offset.checked_add(self.value.len()) else {
+            return false;
+        };
+        data.get(self.offset..end) == Some(self.value.as_slice())
+    }
+}
+
+/// Write types used for the chunks of a chunked write
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChunkWritePolicy {
+    /// Every chunk is written with response
+    #[default]
+    WithResponse,
+    /// Every chunk is written without response
+    WithoutResponse,
+    /// All chunks except the last are written without response, the last one with response
+    /// to confirm the whole message was received
+    FastWithFinalAck,
+}
+
+impl ChunkWritePolicy {
+    /// Write type to use for a chunk
+    #[must_use]
+    pub fn write_type(self, is_last: bool) -> WriteType {
+        match (self, is_last) {
+            (ChunkWritePolicy::WithResponse, _) | (ChunkWritePolicy::FastWithFinalAck, true) => {
+                WriteType::WithResponse
+            }
+            (ChunkWritePolicy::WithoutResponse, _)
+            | (ChunkWritePolicy::FastWithFinalAck, false) => WriteType::WithoutResponse,
+        }
+    }
+}
+
+/// Describes how a protocol splits its messages into notifications, used to reassemble the
+/// complete messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Framing {
+    /// Each message starts with a header of `header_len` bytes that contains the length of the
+    /// payload following the header as a little endian number of `length_size` bytes at
+    /// `length_offset`. Messages are delivered including the header.
+    #[serde(rename_all = "camelCase")]
+    LengthPrefixed {
+        header_len: usize,
+        length_offset: usize,
+        length_size: usize,
+    },
+    /// Each message ends with the given byte, messages are delivered without it
+    Delimited(u8),
+}
+
+impl Framing {
+    /// Returns false if the length field is empty, larger than 8 bytes or outside the header
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            Framing::LengthPrefixed {
+                header_len,
+                length_offset,
+                length_size,
+            } => {
+                (1..=8).contains(&length_size)
+                    && length_offset
+                        .checked_add(length_size)
+                        .is_some_and(|end| end <= header_len)
+            }
+            Framing::Delimited(_) => true,
+        }
+    }
+}
+
+/// Byte order used to parse numeric characteristic values
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+/// Fixed width numbers that can be parsed from a characteristic value
+pub trait FromBleBytes: Sized {
+    /// Parses the value, returns `None` if `data` does not have the exact size of `Self`
+    fn from_ble_bytes(data: &[u8], endianness: Endianness) -> Option<Self>;
+}
+
+macro_rules! impl_from_ble_bytes {
+    ($($t:ty),*) => {
+        $(
+            impl FromBleBytes for $t {
+                fn from_ble_bytes(data: &[u8], endianness: Endianness) -> Option<Self> {
+                    let bytes = data.try_into().ok()?;
+                    Some(match endianness {
+                        Endianness::Little => <$t>::from_le_bytes(bytes),
+                        Endianness::Big => <$t>::from_be_bytes(bytes),
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_from_ble_bytes!(u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
+
+/// Result of a successful [`crate::Handler::loopback_test`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoopbackStats {
+    /// Number of bytes written and received back
+    pub bytes: usize,
+    /// Time from starting the write until the echo was received in milliseconds
+    pub round_trip_ms: f64,
+    /// Payload bytes per second for the whole round trip
+    pub bytes_per_second: f64,
+}
+
+/// Value and descriptor values of a characteristic returned by
+/// [`crate::Handler::inspect_characteristic`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacteristicInspection {
+    pub uuid: Uuid,
+    pub properties: BitFlags<CharProps>,
+    /// Value of the characteristic, `None` if it is not readable or reading it failed
+    pub value: Option<Vec<u8>>,
+    /// Values of the descriptors that could be read
+    pub descriptors: Vec<(Uuid, Vec<u8>)>,
+}
+
+/// Result of reading a single characteristic with the `read_parallel` command
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReadResult {
+    Value(Vec<u8>),
+    Error(String),
+}
+
+impl From<Result<Vec<u8>, error::Error>> for ReadResult {
+    fn from(result: Result<Vec<u8>, error::Error>) -> Self {
+        match result {
+            Ok(value) => Self::Value(value),
+            Err(e) => Self::Error(e.to_string()),
+        }
+    }
+}
+
+/// Active subscription returned by [`crate::Handler::subscriptions`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionInfo {
+    pub uuid: Uuid,
+    /// Value of the last notification, before filters and reassembly are applied
+    pub last_value: Option<Vec<u8>>,
+    /// Time since the last notification in milliseconds, `None` if none was received yet
+    pub last_notification_ms_ago: Option<u64>,
+}
+
+/// Value of the PnP ID characteristic (0x2A50) of the device information service
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PnpId {
+    /// 1 for a Bluetooth SIG assigned company id, 2 for a USB-IF assigned vendor id
+    pub vendor_id_source: u8,
+    pub vendor_id: u16,
+    pub product_id: u16,
+    pub product_version: u16,
+}
+
+impl PnpId {
+    pub(crate) const SIZE: usize = 7;
+
+    pub(crate) fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() != Self::SIZE {
+            return None;
+        }
+        Some(Self {
+            vendor_id_source: data[0],
+            vendor_id: u16::from_le_bytes([data[1], data[2]]),
+            product_id: u16::from_le_bytes([data[3], data[4]]),
+            product_version: u16::from_le_bytes([data[5], data[6]]),
+        })
+    }
+}
+
+/// Value of the Peripheral Preferred Connection Parameters characteristic (0x2A04)
+/// Intervals are in units of 1.25ms and the timeout in units of 10ms, `0xFFFF` means the
+/// device has no preference for that value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreferredConnParams {
+    pub min_interval: u16,
+    pub max_interval: u16,
+    pub slave_latency: u16,
+    pub supervision_timeout: u16,
+}
+
+impl PreferredConnParams {
+    pub(crate) const SIZE: usize = 8;
+
+    pub(crate) fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() != Self::SIZE {
+            return None;
+        }
+        Some(Self {
+            min_interval: u16::from_le_bytes([data[0], data[1]]),
+            max_interval: u16::from_le_bytes([data[2], data[3]]),
+            slave_latency: u16::from_le_bytes([data[4], data[5]]),
+            supervision_timeout: u16::from_le_bytes([data[6], data[7]]),
+        })
+    }
+}
+
+/// Options for scanning
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScanOptions {
+    /// Minimum time in milliseconds between two device updates.
+    /// Updates in between are coalesced into the next one.
+    /// If not set, an update is sent for every poll of the adapter (every 200ms)
+    pub update_interval: Option<u64>,
+    /// Only send devices that are new or changed since the last update instead of all devices
+    pub only_changed: bool,
+    /// Report the GATT services of devices that don't advertise any services but are connected
+    /// already, e.g. by another app. Devices that are not connected are not connected for this,
+    /// use [`ScanOptions::discover_filtered_services`] to connect to matching devices.
+    /// Their services stay empty until a connection is made.
+    pub discover_services: bool,
+    /// Discover the GATT services and characteristics of devices matching a service filter and
+    /// report them in [`BleDevice::gatt_services`], so they can be shown before connecting.
+    /// Every matching device is briefly connected once per scan. Ignored if the scan filter is
+    /// not a service filter.
+    pub discover_filtered_services: bool,
+    /// Maximum number of devices to keep. If more devices are found, only the devices
+    /// with the strongest signal are kept and reported.
+    pub max_devices: Option<usize>,
+    /// Resolve the name of devices that don't advertise one by connecting and reading the
+    /// GAP Device Name characteristic (0x2A00).
+    /// This is disabled by default because every nameless device is briefly connected, which
+    /// costs battery on both sides and delays the scan results of these devices by up to a few
+    /// seconds. Each device is only tried once per scan, the connected device is skipped.
+    pub resolve_names: bool,
+    /// Order of the devices in each update, defaults to sorting by address
+    pub sort_by: SortBy,
+    /// Only report devices matching a service filter that also have this characteristic,
+    /// e.g. to tell apart device variants sharing a service.
+    /// Characteristics are not advertised, so each match is briefly connected once per scan to
+    /// discover its services, see [`ScanOptions::resolve_names`] for the cost. Ignored if the
+    /// scan filter is not a service filter.
+    pub require_characteristic: Option<Uuid>,
+    /// Battery usage vs discovery speed of the scan, defaults to [`ScanMode::LowPower`].
+    /// Only used on Android, ignored on other platforms
+    pub scan_mode: ScanMode,
+    /// Also report devices using LE extended advertising, e.g. long range sensors on the Coded
+    /// PHY that a legacy scan misses.
+    /// Used on Android 8 and newer, on Linux BlueZ reports extended advertisements whenever the
+    /// adapter supports them. Ignored on other platforms.
+    pub extended_advertising: bool,
+    /// Return [`Error::ScanAlreadyRunning`](crate::Error::ScanAlreadyRunning) if a scan is
+    /// already running instead of replacing it
+    pub fail_if_scanning: bool,
+}
+
+/// Trade-off between battery usage and discovery speed of a scan
+/// Only used on Android, other platforms don't allow configuring the scan
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScanMode {
+    /// Scan in long intervals, suited for background scans
+    #[default]
+    LowPower,
+    /// Balance between battery usage and discovery speed
+    Balanced,
+    /// Scan continuously, suited for scans the user is waiting for
+    LowLatency,
+}
+
+/// Order in which scanned devices are reported
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SortBy {
+    /// Sort by address, keeps the order stable between updates
+    #[default]
+    Address,
+    /// Strongest signal first, devices without RSSI are sorted last
+    RssiDesc,
+    /// Sort by name, ties are sorted by address
+    Name,
+}
+
+impl SortBy {
+    /// Sorts the devices in place
+    pub fn sort(self, devices: &mut [BleDevice]) {
+        match self {
+            SortBy::Address => devices.sort(),
+            SortBy::RssiDesc => devices.sort_by(|a, b| b.rssi.cmp(&a.rssi).then_with(|| a.cmp(b))),
+            SortBy::Name => devices.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.cmp(b))),
+        }
+    }
+}
+
 /// Filter for discovering devices.
 /// Only devices matching the filter will be returned by the `handler::discover` method
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum ScanFilter {
+    #[default]
     None,
     /// Matches if the device advertises the specified service.
     Service(Uuid),
@@ -182,3 +788,413 @@ pub enum ScanFilter {
     /// that are 1 in the mask
     ManufacturerDataMasked(u16, Vec<u8>, Vec<u8>),
 }
+
+/// State of the bluetooth adapter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AdapterState {
+    Unknown,
+    PoweredOn,
+    PoweredOff,
+}
+
+impl From<btleplug::api::CentralState> for AdapterState {
+    fn from(state: btleplug::api::CentralState) -> Self {
+        match state {
+            btleplug::api::CentralState::Unknown => AdapterState::Unknown,
+            btleplug::api::CentralState::PoweredOn => AdapterState::PoweredOn,
+            btleplug::api::CentralState::PoweredOff => AdapterState::PoweredOff,
+        }
+    }
+}
+
+/// Reason for a disconnect
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DisconnectReason {
+    /// The disconnect was requested by calling `disconnect`
+    Requested,
+    /// The connection was closed by the device or lost
+    ConnectionLost,
+}
+
+/// Transport used to connect to dual-mode devices
+/// Only used on Android, other platforms choose the transport automatically
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Transport {
+    /// Let the platform decide
+    Auto,
+    /// Bluetooth Low Energy
+    #[default]
+    Le,
+    /// Bluetooth classic
+    BrEdr,
+}
+
+/// Options for connecting to a device
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ConnectOptions {
+    /// Transport to use for dual-mode devices, defaults to LE. Ignored on platforms other than Android
+    pub transport: Transport,
+    /// Number of additional scans to run if the device was not found by the scan before
+    /// connecting. Helps with devices that have long advertising intervals. Defaults to 2
+    pub scan_retries: u32,
+    /// Stop a running scan before connecting. Some platforms fail to connect while scanning,
+    /// so this is enabled by default. If disabled, connecting during a scan returns
+    /// [`Error::ScanInProgress`](crate::Error::ScanInProgress)
+    pub stop_scan: bool,
+    /// User defined tag for the device, e.g. "left sensor".
+    /// The tag is stored by address and included in the devices reported by scans and
+    /// [`Handler::connected_device`](crate::Handler::connected_device), so it survives reconnects
+    pub tag: Option<String>,
+    /// What to do if a different device is still connected, defaults to disconnecting it
+    pub existing_connection: ExistingConnectionPolicy,
+    /// Disable notifications of all characteristics on the device right after connecting,
+    /// before any subscription is made.
+    /// Some devices keep the notifications enabled by a previous connection and keep sending
+    /// them, this starts every connection from a known state. Disabled by default
+    pub reset_notifications: bool,
+}
+
+impl Default for ConnectOptions {
+    fn default() -> Self {
+        Self {
+            transport: Transport::default(),
+            scan_retries: 2,
+            stop_scan: true,
+            tag: None,
+            existing_connection: ExistingConnectionPolicy::default(),
+            reset_notifications: false,
+        }
+    }
+}
+
+/// Behavior when connecting while a different device is connected
+/// Only one device can be connected at a time
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExistingConnectionPolicy {
+    /// Disconnect the connected device before connecting to the new one
+    #[default]
+    DisconnectExisting,
+    /// Return [`Error::AnotherDeviceConnected`](crate::Error::AnotherDeviceConnected)
+    Reject,
+}
+
+/// Write that failed after it was handed to the platform, see `Handler::set_write_error_channel`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WriteError {
+    pub characteristic: Uuid,
+    /// Platform specific description of the failure, e.g. the GATT status on Android
+    pub message: String,
+}
+
+/// Device stored with `Handler::remember_device` to reconnect on the next app launch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RememberedDevice {
+    pub address: String,
+    pub name: String,
+    /// Services advertised by the device when it was remembered
+    pub services: Vec<Uuid>,
+    pub tag: Option<String>,
+}
+
+/// Information about an established connection, returned by `Handler::connect`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectResult {
+    /// True if the services were taken from the platform cache instead of running a
+    /// service discovery. Call `Handler::refresh_services` if the cache might be outdated.
+    pub used_cached_services: bool,
+    pub service_count: usize,
+}
+
+/// Detailed connection status of the handler
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConnectionStatus {
+    Disconnected,
+    /// A connection attempt is running
+    Connecting,
+    Connected,
+    /// A previous connection attempt failed and it is retried
+    Reconnecting,
+}
+
+/// Capabilities of the local Bluetooth controller
+/// Fields are `None` if the platform doesn't allow to query them
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AdapterFeatures {
+    /// LE 2M PHY for higher throughput
+    pub le_2m_phy: Option<bool>,
+    /// LE Coded PHY for long range
+    pub le_coded_phy: Option<bool>,
+    /// Extended advertising
+    pub extended_advertising: Option<bool>,
+    /// Maximum number of simultaneous connections
+    pub max_connections: Option<u32>,
+}
+
+/// Physical layer used by a connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Phy {
+    Le1M,
+    Le2M,
+    LeCoded,
+}
+
+/// Snapshot of the parameters of the current connection
+/// Fields are `None` if the platform doesn't allow to query them
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LinkInfo {
+    /// Negotiated MTU, see `Handler::mtu`
+    pub mtu: Option<u16>,
+    /// PHY used for sending, only available on Android
+    pub tx_phy: Option<Phy>,
+    /// PHY used for receiving, only available on Android
+    pub rx_phy: Option<Phy>,
+    /// Connection interval in milliseconds, none of the supported platforms expose it yet
+    pub connection_interval_ms: Option<f64>,
+    /// Signal strength of the connection. On platforms other than Android this is the
+    /// RSSI of the last advertisement, which might be outdated or missing while connected
+    pub rssi: Option<i16>,
+}
+
+/// Stages of a connection attempt, see `Handler::connect_with_progress`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConnectProgress {
+    /// The link to the device is being established
+    Connecting,
+    /// The link is established
+    Connected,
+    /// Services and characteristics are being discovered
+    DiscoveringServices,
+    /// Services and characteristics are known
+    ServicesDiscovered,
+    /// The device is ready to use
+    Ready,
+}
+
+/// Event emitted by the handler whenever its status changes
+/// See `Handler::events`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BleStatusEvent {
+    ScanStarted,
+    ScanStopped,
+    /// A device was seen for the first time during the current scan
+    DeviceDiscovered(BleDevice),
+    /// A connection to the device with the given address was established
+    Connected {
+        address: String,
+        /// Tag of the device, see [`ConnectOptions::tag`]
+        tag: Option<String>,
+    },
+    Disconnected(DisconnectReason),
+    AdapterStateChanged(AdapterState),
+    /// The notification stream ended while the device is still connected.
+    /// No further notifications will be received until reconnecting.
+    NotificationsStopped,
+    /// A running scan was replaced by a new scan, the update channel of the replaced scan
+    /// was closed
+    ScanSuperseded,
+    /// The device sent a Service Changed indication and the services were refreshed.
+    /// Services and characteristics queried before should be fetched again.
+    ServicesChanged,
+    /// The connected device uses these characteristic UUIDs in more than one service.
+    /// Use the service qualified methods like `Handler::send_data_in_service` for them,
+    /// otherwise the characteristic of a primary service is used.
+    DuplicateCharacteristics(Vec<Uuid>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notify_filter_matches_value_at_offset() {
+        let filter = NotifyFilter {
+            offset: 1,
+            value: vec![0x02, 0x03],
+        };
+        assert!(filter.matches(&[0x01, 0x02, 0x03, 0x04]));
+        assert!(!filter.matches(&[0x02, 0x03]));
+        assert!(!filter.matches(&[0x01, 0x02]));
+    }
+
+    #[test]
+    fn notify_filter_offset_overflow_does_not_match() {
+        let filter = NotifyFilter {
+            offset: usize::MAX,
+            value: vec![0x01],
+        };
+        assert!(!filter.matches(&[0x01]));
+    }
+
+    #[test]
+    fn framing_length_field_must_fit_the_header() {
+        let framing = |length_offset, length_size| Framing::LengthPrefixed {
+            header_len: 4,
+            length_offset,
+            length_size,
+        };
+        assert!(framing(2, 2).is_valid());
+        assert!(!framing(3, 2).is_valid());
+        assert!(!framing(0, 0).is_valid());
+        assert!(!framing(usize::MAX, 2).is_valid());
+        assert!(Framing::Delimited(b'\n').is_valid());
+    }
+
+    const HEART_RATE_MEASUREMENT: Uuid = uuid::uuid!("00002A37-0000-1000-8000-00805F9B34FB");
+
+    #[test]
+    fn gatt_uuid_from_number() {
+        let uuid: GattUuid = serde_json::from_value(serde_json::json!(0x2A37)).unwrap();
+        assert_eq!(uuid.0, HEART_RATE_MEASUREMENT);
+    }
+
+    #[test]
+    fn gatt_uuid_from_short_string() {
+        for text in ["2A37", "2a37", "0x2A37", "0X2A37", " 2A37 "] {
+            assert_eq!(
+                text.parse::<GattUuid>().unwrap().0,
+                HEART_RATE_MEASUREMENT,
+                "{text}"
+            );
+        }
+        let uuid: GattUuid = serde_json::from_value(serde_json::json!("0x2A37")).unwrap();
+        assert_eq!(uuid.0, HEART_RATE_MEASUREMENT);
+    }
+
+    #[test]
+    fn gatt_uuid_from_32_bit_string() {
+        assert_eq!(
+            "12345678".parse::<GattUuid>().unwrap().0,
+            uuid::uuid!("12345678-0000-1000-8000-00805F9B34FB")
+        );
+    }
+
+    #[test]
+    fn gatt_uuid_from_full_string() {
+        let full = "51FF12BB-3ED8-46E5-B4F9-D64E2FEC021B";
+        assert_eq!(
+            full.parse::<GattUuid>().unwrap().0,
+            Uuid::parse_str(full).unwrap()
+        );
+    }
+
+    #[test]
+    fn gatt_uuid_rejects_invalid_strings() {
+        for text in ["", "0x", "xyz", "123456789", "51FF12BB-3ED8-46E5-B4F9"] {
+            assert!(text.parse::<GattUuid>().is_err(), "{text}");
+        }
+        assert!(serde_json::from_value::<GattUuid>(serde_json::json!("xyz")).is_err());
+    }
+
+    #[test]
+    fn pnp_id_from_little_endian_bytes() {
+        let pnp_id = PnpId::from_bytes(&[0x01, 0x0D, 0x00, 0x34, 0x12, 0x02, 0x01]).unwrap();
+        assert_eq!(
+            pnp_id,
+            PnpId {
+                vendor_id_source: 1,
+                vendor_id: 0x000D,
+                product_id: 0x1234,
+                product_version: 0x0102,
+            }
+        );
+    }
+
+    #[test]
+    fn pnp_id_rejects_wrong_length() {
+        assert_eq!(PnpId::from_bytes(&[0x01; PnpId::SIZE - 1]), None);
+        assert_eq!(PnpId::from_bytes(&[0x01; PnpId::SIZE + 1]), None);
+        assert_eq!(PnpId::from_bytes(&[]), None);
+    }
+
+    #[test]
+    fn preferred_conn_params_from_little_endian_bytes() {
+        let params =
+            PreferredConnParams::from_bytes(&[0x18, 0x00, 0x28, 0x00, 0x00, 0x00, 0xFF, 0xFF])
+                .unwrap();
+        assert_eq!(
+            params,
+            PreferredConnParams {
+                min_interval: 0x0018,
+                max_interval: 0x0028,
+                slave_latency: 0,
+                supervision_timeout: 0xFFFF,
+            }
+        );
+    }
+
+    #[test]
+    fn preferred_conn_params_rejects_wrong_length() {
+        assert_eq!(
+            PreferredConnParams::from_bytes(&[0x01; PreferredConnParams::SIZE - 1]),
+            None
+        );
+        assert_eq!(
+            PreferredConnParams::from_bytes(&[0x01; PreferredConnParams::SIZE + 1]),
+            None
+        );
+    }
+
+    #[test]
+    fn from_ble_bytes_integers() {
+        assert_eq!(u8::from_ble_bytes(&[0xFE], Endianness::Little), Some(0xFE));
+        assert_eq!(i8::from_ble_bytes(&[0xFE], Endianness::Big), Some(-2));
+        assert_eq!(
+            u16::from_ble_bytes(&[0x34, 0x12], Endianness::Little),
+            Some(0x1234)
+        );
+        assert_eq!(
+            i16::from_ble_bytes(&[0xFF, 0xFE], Endianness::Big),
+            Some(-2)
+        );
+        assert_eq!(
+            u32::from_ble_bytes(&[0x78, 0x56, 0x34, 0x12], Endianness::Little),
+            Some(0x1234_5678)
+        );
+        assert_eq!(
+            i32::from_ble_bytes(&[0xFF, 0xFF, 0xFF, 0xFE], Endianness::Big),
+            Some(-2)
+        );
+        assert_eq!(
+            u64::from_ble_bytes(&[1, 0, 0, 0, 0, 0, 0, 0], Endianness::Little),
+            Some(1)
+        );
+        assert_eq!(i64::from_ble_bytes(&[0xFF; 8], Endianness::Big), Some(-1));
+    }
+
+    #[test]
+    fn from_ble_bytes_floats() {
+        assert_eq!(
+            f32::from_ble_bytes(&1.5f32.to_le_bytes(), Endianness::Little),
+            Some(1.5)
+        );
+        assert_eq!(
+            f64::from_ble_bytes(&(-2.25f64).to_be_bytes(), Endianness::Big),
+            Some(-2.25)
+        );
+    }
+
+    #[test]
+    fn from_ble_bytes_rejects_wrong_size() {
+        assert_eq!(u16::from_ble_bytes(&[0x01], Endianness::Little), None);
+        assert_eq!(
+            u16::from_ble_bytes(&[0x01, 0x02, 0x03], Endianness::Big),
+            None
+        );
+        assert_eq!(u32::from_ble_bytes(&[], Endianness::Little), None);
+        assert_eq!(f64::from_ble_bytes(&[0; 4], Endianness::Little), None);
+    }
+}
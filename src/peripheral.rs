@@ -0,0 +1,179 @@
+//! Minimal peripheral role to advertise a service and answer reads and writes of its
+//! characteristics.
+//! Only available on Linux with the `peripheral` feature, the GATT application and the
+//! advertisement are registered with BlueZ through [`bluer`].
+use std::sync::Arc;
+
+use bluer::{
+    adv::{Advertisement, AdvertisementHandle},
+    gatt::local::{
+        Application, ApplicationHandle, Characteristic, CharacteristicRead, CharacteristicWrite,
+        CharacteristicWriteMethod, Service,
+    },
+};
+use futures::FutureExt;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::error::Error;
+
+type ReadCallback = Arc<dyn Fn() -> Vec<u8> + Send + Sync>;
+type WriteCallback = Arc<dyn Fn(Vec<u8>) + Send + Sync>;
+
+/// Characteristic served by a [`PeripheralServer`]
+/// The characteristic is readable if a read callback is set and writable if a write callback
+/// is set.
+pub struct LocalCharacteristic {
+    uuid: Uuid,
+    read: Option<ReadCallback>,
+    write: Option<WriteCallback>,
+}
+
+impl LocalCharacteristic {
+    #[must_use]
+    pub fn new(uuid: Uuid) -> Self {
+        Self {
+            uuid,
+            read: None,
+            write: None,
+        }
+    }
+
+    /// Sets the callback that returns the value for read requests
+    #[must_use]
+    pub fn on_read(mut self, callback: impl Fn() -> Vec<u8> + Send + Sync + 'static) -> Self {
+        self.read = Some(Arc::new(callback));
+        self
+    }
+
+    /// Sets the callback that receives the value of write requests, with or without response
+    #[must_use]
+    pub fn on_write(mut self, callback: impl Fn(Vec<u8>) + Send + Sync + 'static) -> Self {
+        self.write = Some(Arc::new(callback));
+        self
+    }
+
+    fn into_gatt(self) -> Characteristic {
+        let read = self.read.map(|read| CharacteristicRead {
+            read: true,
+            fun: Box::new(move |_req| {
+                let value = read();
+                async move { Ok(value) }.boxed()
+            }),
+            ..Default::default()
+        });
+        let write = self.write.map(|write| CharacteristicWrite {
+            write: true,
+            write_without_response: true,
+            method: CharacteristicWriteMethod::Fun(Box::new(move |value, _req| {
+                write(value);
+                async move { Ok(()) }.boxed()
+            })),
+            ..Default::default()
+        });
+        Characteristic {
+            uuid: self.uuid,
+            read,
+            write,
+            ..Default::default()
+        }
+    }
+}
+
+/// GATT server with a single primary service, the counterpart of [`Handler`](crate::Handler)
+/// for apps that need to act as a BLE peripheral
+/// # Example
+/// ```no_run
+/// use tauri::async_runtime;
+/// use tauri_plugin_blec::peripheral::{LocalCharacteristic, PeripheralServer};
+/// use uuid::uuid;
+///
+/// async_runtime::block_on(async {
+///     let server = PeripheralServer::new().characteristic(
+///         LocalCharacteristic::new(uuid!("51FF12BB-3ED8-46E5-B4F9-D64E2FEC021B"))
+///             .on_read(|| vec![0x01, 0x02])
+///             .on_write(|data| println!("received {data:?}")),
+///     );
+///     let advertising = server
+///         .advertise(uuid!("A07498CA-AD5B-474E-940D-16F1FBE7E8CD"), "blec")
+///         .await
+///         .unwrap();
+///     // ...
+///     advertising.stop();
+/// });
+/// ```
+#[derive(Default)]
+pub struct PeripheralServer {
+    characteristics: Vec<LocalCharacteristic>,
+}
+
+impl PeripheralServer {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a characteristic to the served service
+    #[must_use]
+    pub fn characteristic(mut self, characteristic: LocalCharacteristic) -> Self {
+        self.characteristics.push(characteristic);
+        self
+    }
+
+    /// Serves the characteristics in a primary service with the given UUID and starts
+    /// advertising it with the given local name on the default adapter
+    /// Serving and advertising continue until [`Advertising::stop`] is called or the
+    /// [`Advertising`] is dropped
+    /// # Errors
+    /// Returns an error if there is no adapter or registering with BlueZ fails
+    pub async fn advertise(
+        self,
+        service_uuid: Uuid,
+        local_name: &str,
+    ) -> Result<Advertising, Error> {
+        let session = bluer::Session::new().await?;
+        let adapter = session.default_adapter().await?;
+        debug!("advertising on {}", adapter.name());
+
+        let app = Application {
+            services: vec![Service {
+                uuid: service_uuid,
+                primary: true,
+                characteristics: self
+                    .characteristics
+                    .into_iter()
+                    .map(LocalCharacteristic::into_gatt)
+                    .collect(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let app = adapter.serve_gatt_application(app).await?;
+        let advertisement = Advertisement {
+            service_uuids: [service_uuid].into_iter().collect(),
+            local_name: Some(local_name.to_string()),
+            ..Default::default()
+        };
+        let advertisement = adapter.advertise(advertisement).await?;
+        Ok(Advertising {
+            _advertisement: advertisement,
+            _app: app,
+            _session: session,
+        })
+    }
+}
+
+/// Running advertisement and GATT application of a [`PeripheralServer`]
+/// Both are unregistered from BlueZ when this is dropped
+pub struct Advertising {
+    _advertisement: AdvertisementHandle,
+    _app: ApplicationHandle,
+    _session: bluer::Session,
+}
+
+impl Advertising {
+    /// Stops advertising and removes the served service
+    pub fn stop(self) {
+        debug!("stopping advertising");
+    }
+}